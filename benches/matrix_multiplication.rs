@@ -0,0 +1,54 @@
+//! Compares matrix multiplication with the blocked kernel behind `*` and `h_linear_composition`
+//! against the previous column-by-column implementation, which transformed every column of the
+//! right-hand matrix with `h_linear_transform` and appended it with `add_col`.
+//!
+//! Run with `cargo bench`, or `cargo bench --features parallel` to include the multi-threaded path.
+//! The column-by-column version is skipped for the largest size, where it takes too long to be useful.
+
+use h_math::prelude::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+fn column_by_column(matrix2: &HMatrix<f64>, matrix1: &HMatrix<f64>) -> HMatrix<f64> {
+    let mut new_matrix: HMatrix<f64> = HMatrix::new();
+    for i in 0..matrix1.shape().1 {
+        let column: Vec<f64> = matrix1.get_col(i).unwrap().h_linear_transform(matrix2).unwrap();
+        new_matrix.add_col(column).unwrap();
+    }
+    new_matrix
+}
+
+/// Returns the fastest of `repetitions` runs, which is the least affected by other work on the machine.
+fn fastest<F>(repetitions: u32, mut f: F) -> Duration
+where
+    F: FnMut() -> HMatrix<f64>,
+{
+    let mut best: Duration = Duration::MAX;
+    for _ in 0..repetitions {
+        let start: Instant = Instant::now();
+        black_box(f());
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+fn main() {
+    let parallel: &str = if cfg!(feature = "parallel") { "on" } else { "off" };
+    println!("parallel feature: {}", parallel);
+    println!("{:>6} {:>18} {:>14} {:>9}", "n", "column-by-column", "blocked", "speedup");
+
+    for n in [64, 128, 256, 512, 1024] {
+        let a: HMatrix<f64> = HMatrix::from_fn(n, n, |i, j| ((i * 7 + j * 3) % 11) as f64 - 5.0);
+        let b: HMatrix<f64> = HMatrix::from_fn(n, n, |i, j| ((i * 5 + j * 2) % 13) as f64 / 13.0);
+        let repetitions: u32 = if n <= 256 { 5 } else { 1 };
+
+        let blocked: Duration = fastest(repetitions, || &a * &b);
+        if n <= 512 {
+            let old: Duration = fastest(repetitions, || column_by_column(&a, &b));
+            let speedup: f64 = old.as_secs_f64() / blocked.as_secs_f64();
+            println!("{:>6} {:>18.2?} {:>14.2?} {:>8.1}x", n, old, blocked, speedup);
+        } else {
+            println!("{:>6} {:>18} {:>14.2?} {:>9}", n, "-", blocked, "-");
+        }
+    }
+}
@@ -1,3 +1,9 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::complex::HComplex;
+
 // --------------------------------- Algebra ------------------------------
 
 
@@ -10,6 +16,7 @@
 /// let (root1, root2) = h_quadratic_equation(1.0, 2.0, 5.0);
 /// The function will panic with the message "No real roots exist" because the discriminant (b^2 - 4ac) is negative.
 /// Note: The order of the roots in the returned tuple is not guaranteed, so root1 may be the larger or smaller root depending on the coefficients.
+/// Use `h_solve_quadratic` to handle every case, including complex roots and `a == 0`, without panicking.
 pub fn h_quadratic_equation<A,B,C>(a: A, b: B, c: C) -> (f64, f64)
 where
     A: Copy + Into<f64>,
@@ -32,3 +39,1169 @@ where
 
     (root1, root2)
 }
+
+
+/// The solutions of a quadratic equation `ax^2 + bx + c = 0`, returned by `h_solve_quadratic`.
+/// Every combination of coefficients has a variant, including the degenerate ones where `a` is zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HQuadraticRoots {
+    /// Two distinct real roots, in ascending order.
+    TwoReal(f64, f64),
+    /// One real root of multiplicity two, when the discriminant is exactly zero.
+    Repeated(f64),
+    /// Two complex conjugate roots, with the positive imaginary part first.
+    ComplexPair(HComplex, HComplex),
+    /// `a` is zero, so the equation is linear, `bx + c = 0`, with this single root.
+    Linear(f64),
+    /// `a` and `b` are zero but `c` is not, so the equation `c = 0` has no solution.
+    NoSolution,
+    /// All coefficients are zero, so every `x` is a solution.
+    InfiniteSolutions,
+}
+
+impl HQuadraticRoots {
+    /// Returns the real roots in ascending order, once per distinct root.
+    /// The result is empty for a complex pair and for the no/infinite solution cases.
+    pub fn real_roots(&self) -> Vec<f64> {
+        match self {
+            HQuadraticRoots::TwoReal(root1, root2) => vec![*root1, *root2],
+            HQuadraticRoots::Repeated(root) | HQuadraticRoots::Linear(root) => vec![*root],
+            _ => Vec::new(),
+        }
+    }
+}
+
+
+/// Solves the quadratic equation `ax^2 + bx + c = 0` without panicking, for any coefficients.
+/// The roots are computed with the numerically stable form of the quadratic formula:
+/// `q = -(b + sign(b)·sqrt(b^2 - 4ac)) / 2`, `x1 = q / a`, `x2 = c / q`.
+/// The textbook formula `(-b ± sqrt(b^2 - 4ac)) / 2a` subtracts two nearly equal numbers for one of the roots
+/// when `b^2` is much larger than `4ac`, and loses most of its digits; this form never does.
+/// Every intermediate value is divided by a power of two `s` close to the size of the roots times `|a|`, chosen from
+/// `|b| / 2` and `sqrt(|a|)·sqrt(|c|)` separately, so neither `b^2` nor `ac` overflows or underflows, even when the
+/// coefficients are hundreds of orders of magnitude apart.
+///
+/// Example:
+///
+/// assert_eq!(h_solve_quadratic(1, -3, 2), HQuadraticRoots::TwoReal(1.0, 2.0));
+/// assert_eq!(h_solve_quadratic(1, 2, 1), HQuadraticRoots::Repeated(-1.0));
+/// assert_eq!(h_solve_quadratic(1, 2, 5), HQuadraticRoots::ComplexPair(HComplex::new(-1.0, 2.0), HComplex::new(-1.0, -2.0)));
+/// assert_eq!(h_solve_quadratic(0, 2, -4), HQuadraticRoots::Linear(2.0));
+pub fn h_solve_quadratic<A, B, C>(a: A, b: B, c: C) -> HQuadraticRoots
+where
+    A: Copy + Into<f64>,
+    B: Copy + Into<f64>,
+    C: Copy + Into<f64>,
+{
+    let (a, b, c): (f64, f64, f64) = (a.into(), b.into(), c.into());
+    if a == 0.0 {
+        if b == 0.0 {
+            if c == 0.0 {
+                return HQuadraticRoots::InfiniteSolutions;
+            }
+            return HQuadraticRoots::NoSolution;
+        }
+        return HQuadraticRoots::Linear(-c / b);
+    }
+
+    // With h = b / 2 the roots are (-h ± sqrt(h^2 - ac)) / a. Both terms under the square root are at most s^2,
+    // so dividing them by s^2 keeps them in range, and the roots are then rescaled with s / a.
+    let half_b: f64 = 0.5 * b;
+    let geometric_mean: f64 = a.abs().sqrt() * c.abs().sqrt();
+    let size: f64 = half_b.abs().max(geometric_mean);
+    if size == 0.0 {
+        return HQuadraticRoots::Repeated(0.0);
+    }
+    let s: f64 = 2f64.powi((size.log2().round() as i32).clamp(-1022, 1023));
+    let u: f64 = half_b / s;
+    // ac / s^2 is computed directly when that is exact enough, which keeps integer coefficients exact,
+    // and from the geometric mean when a / s or c / s leaves the normal range.
+    let (a_s, c_s): (f64, f64) = (a / s, c / s);
+    let product: f64 = if c == 0.0 {
+        0.0
+    } else if a_s.is_finite() && c_s.is_normal() && (a_s * c_s).is_normal() {
+        a_s * c_s
+    } else {
+        let v: f64 = geometric_mean / s;
+        (v * v).copysign(a * c.signum())
+    };
+    let discriminant: f64 = u * u - product;
+    let root_scale: f64 = s / a;
+
+    if discriminant == 0.0 {
+        return HQuadraticRoots::Repeated(-u * root_scale);
+    }
+    if discriminant < 0.0 {
+        let re: f64 = -u * root_scale;
+        let im: f64 = (-discriminant).sqrt() * root_scale.abs();
+        return HQuadraticRoots::ComplexPair(HComplex::new(re, im), HComplex::new(re, -im));
+    }
+
+    // q = -(h + sign(h)·sqrt(h^2 - ac)) / s never cancels; the roots are q·s / a and c / (q·s).
+    let q: f64 = -(u + discriminant.sqrt().copysign(u));
+    let root1: f64 = q * root_scale;
+    let root2: f64 = c_s / q;
+    HQuadraticRoots::TwoReal(root1.min(root2), root1.max(root2))
+}
+
+
+/// Solves the cubic equation `ax^3 + bx^2 + cx + d = 0` and returns its three roots, counted with multiplicity.
+/// Returns None when `a` is zero; use `h_solve_quadratic` for the lower degree equation. If a coefficient is NaN,
+/// all three roots are NaN.
+/// The largest real root is found with Cardano's formula, or with the trigonometric (Viète) form when all three
+/// roots are real, which avoids complex cube roots. The other two roots solve the quadratic that is left after
+/// dividing that root out. The variable is scaled by a power of two first, so coefficients of very different
+/// sizes, such as a tiny `a`, do not overflow.
+/// Roots are sorted: real roots first in ascending order, then complex roots by real part, positive imaginary part first.
+///
+/// Example:
+///
+/// let roots = h_solve_cubic(1, -6, 11, -6).unwrap(); // (x - 1)(x - 2)(x - 3)
+/// The real parts of roots are approximately [1.0, 2.0, 3.0] and the imaginary parts are 0.
+pub fn h_solve_cubic<A, B, C, D>(a: A, b: B, c: C, d: D) -> Option<[HComplex; 3]>
+where
+    A: Copy + Into<f64>,
+    B: Copy + Into<f64>,
+    C: Copy + Into<f64>,
+    D: Copy + Into<f64>,
+{
+    let (a, b, c, d): (f64, f64, f64, f64) = (a.into(), b.into(), c.into(), d.into());
+    if a == 0.0 {
+        return None;
+    }
+    if a.is_nan() || b.is_nan() || c.is_nan() || d.is_nan() {
+        return Some([HComplex::new(f64::NAN, f64::NAN); 3]);
+    }
+    if d == 0.0 {
+        // x = 0 is a root, and the other two solve ax^2 + bx + c = 0.
+        let [root2, root3] = quadratic_roots_pair(h_solve_quadratic(a, b, c));
+        let mut roots: [HComplex; 3] = [HComplex::from(0.0), root2, root3];
+        sort_roots(&mut roots);
+        return Some(roots);
+    }
+
+    // Substitute x = 2^k y, where 2^k is close to the size of the largest root. The monic cubic in y then has
+    // coefficients of at most about one, so neither b / a nor the products below can overflow.
+    let log_a: f64 = a.abs().log2();
+    let k: i32 = [(b, 1.0), (c, 2.0), (d, 3.0)]
+        .iter()
+        .map(|(coefficient, degree)| (coefficient.abs().log2() - log_a) / degree)
+        .fold(f64::NEG_INFINITY, f64::max)
+        .round() as i32;
+    let a_exponent: i32 = log_a.round() as i32;
+    let a_mantissa: f64 = scale_by_power_of_two(a, -a_exponent);
+    let scaled = |coefficient: f64, degree: i32| scale_by_power_of_two(coefficient, -a_exponent - degree * k) / a_mantissa;
+    let (b_y, c_y, d_y): (f64, f64, f64) = (scaled(b, 1), scaled(c, 2), scaled(d, 3));
+
+    // Substituting y = t - b_y/3 gives the depressed cubic t^3 + pt + q = 0.
+    let shift: f64 = b_y / 3.0;
+    let p: f64 = c_y - b_y * shift;
+    let q: f64 = 2.0 * shift * shift * shift - shift * c_y + d_y;
+
+    let half_q: f64 = q / 2.0;
+    let third_p: f64 = p / 3.0;
+    let discriminant: f64 = half_q * half_q + third_p * third_p * third_p;
+    let discriminant_scale: f64 = half_q * half_q + (third_p * third_p * third_p).abs();
+
+    let roots: [f64; 3] = if discriminant.abs() <= 64.0 * f64::EPSILON * discriminant_scale {
+        if p == 0.0 {
+            [0.0; 3]
+        } else {
+            // A double root and a simple root.
+            let simple: f64 = 3.0 * q / p;
+            let double: f64 = -1.5 * q / p;
+            [simple, double, double]
+        }
+    } else if discriminant > 0.0 {
+        // Take the cube root of the larger term, so the sum under it does not cancel.
+        // Only the real root u + v is needed, the complex pair comes from the deflation below.
+        let u: f64 = (-half_q - discriminant.sqrt().copysign(half_q)).cbrt();
+        let v: f64 = if u == 0.0 { 0.0 } else { -third_p / u };
+        [u + v; 3]
+    } else {
+        let radius: f64 = 2.0 * (-third_p).sqrt();
+        let cos_arg: f64 = (3.0 * q / (p * radius)).clamp(-1.0, 1.0);
+        let theta: f64 = cos_arg.acos() / 3.0;
+        let step: f64 = 2.0 * std::f64::consts::PI / 3.0;
+        [radius * theta.cos(), radius * (theta - step).cos(), radius * (theta - 2.0 * step).cos()]
+    };
+
+    // When the roots differ greatly in size, the small ones drown in the rounding errors of p and q.
+    // Keep the largest real root, polished with Newton's method, and recover the other two from the
+    // quadratic left after dividing it out, whose coefficients come straight from a, b, c and d.
+    let largest: f64 = roots
+        .iter()
+        .map(|root| root - shift)
+        .fold(0.0, |acc: f64, y| if y.is_nan() || y.abs() > acc.abs() { y } else { acc });
+    let largest: f64 = scale_by_power_of_two(polish_cubic_root(largest, b_y, c_y, d_y), k);
+    // Dividing by (x - r) leaves ax^2 + ex + f with f = -d / r, and e either from the top, b + ar,
+    // or from the bottom, (f - c) / r. Use whichever of the two has the smaller rounding error.
+    let constant: f64 = -d / largest;
+    let forward_error: f64 = b.abs() + (a * largest).abs();
+    let backward_error: f64 = (constant.abs() + c.abs()) / largest.abs();
+    let linear: f64 = if forward_error <= backward_error { b + a * largest } else { (constant - c) / largest };
+    let [root2, root3] = quadratic_roots_pair(h_solve_quadratic(a, linear, constant));
+    let mut roots: [HComplex; 3] = [HComplex::from(largest), root2, root3];
+    sort_roots(&mut roots);
+    Some(roots)
+}
+
+
+/// Multiplies `x` by `2^exponent`, in steps so that the power of two itself never overflows.
+/// Each step is exact, so the result is only rounded if it leaves the normal range.
+fn scale_by_power_of_two(x: f64, exponent: i32) -> f64 {
+    let mut x: f64 = x;
+    let mut remaining: i32 = exponent;
+    while remaining != 0 {
+        let step: i32 = remaining.clamp(-1000, 1000);
+        x *= 2f64.powi(step);
+        remaining -= step;
+    }
+    x
+}
+
+
+/// Improves a real root of `x^3 + bx^2 + cx + d` with a few Newton steps, keeping a step only if it
+/// reduces the value of the polynomial.
+fn polish_cubic_root(root: f64, b: f64, c: f64, d: f64) -> f64 {
+    let value = |x: f64| ((x + b) * x + c) * x + d;
+    let mut root: f64 = root;
+    for _ in 0..4 {
+        let slope: f64 = (3.0 * root + 2.0 * b) * root + c;
+        if slope == 0.0 {
+            break;
+        }
+        let next: f64 = root - value(root) / slope;
+        if !next.is_finite() || value(next).abs() >= value(root).abs() {
+            break;
+        }
+        root = next;
+    }
+    root
+}
+
+
+/// The two roots of a proper quadratic (`a != 0`) as complex numbers, repeated roots included twice.
+fn quadratic_roots_pair(roots: HQuadraticRoots) -> [HComplex; 2] {
+    match roots {
+        HQuadraticRoots::TwoReal(root1, root2) => [HComplex::from(root1), HComplex::from(root2)],
+        HQuadraticRoots::Repeated(root) => [HComplex::from(root); 2],
+        HQuadraticRoots::ComplexPair(root1, root2) => [root1, root2],
+        _ => unreachable!("a quadratic with a nonzero leading coefficient has two roots"),
+    }
+}
+
+
+/// Solves the quartic equation `ax^4 + bx^3 + cx^2 + dx + e = 0` and returns its four roots, counted with multiplicity.
+/// Returns None when `a` is zero; use `h_solve_cubic` for the lower degree equation.
+/// Uses Ferrari's method: the depressed quartic is split into two quadratics with the help of a real root of
+/// its resolvent cubic. Biquadratic equations (no odd terms after depressing) are solved as a quadratic in `x^2`.
+/// Roots are sorted like those of `h_solve_cubic`.
+///
+/// Example:
+///
+/// let roots = h_solve_quartic(1, 0, 0, 0, -1).unwrap(); // x^4 - 1
+/// The roots are approximately -1, 1, i and -i, in that order.
+pub fn h_solve_quartic<A, B, C, D, E>(a: A, b: B, c: C, d: D, e: E) -> Option<[HComplex; 4]>
+where
+    A: Copy + Into<f64>,
+    B: Copy + Into<f64>,
+    C: Copy + Into<f64>,
+    D: Copy + Into<f64>,
+    E: Copy + Into<f64>,
+{
+    let a: f64 = a.into();
+    if a == 0.0 {
+        return None;
+    }
+    let (b, c, d, e): (f64, f64, f64, f64) = (b.into() / a, c.into() / a, d.into() / a, e.into() / a);
+
+    // Substituting x = y - b/4 gives the depressed quartic y^4 + py^2 + qy + r = 0.
+    let shift: f64 = b / 4.0;
+    let shift2: f64 = shift * shift;
+    let p: f64 = c - 6.0 * shift2;
+    let q: f64 = d - 2.0 * c * shift + 8.0 * shift2 * shift;
+    let r: f64 = e - d * shift + c * shift2 - 3.0 * shift2 * shift2;
+
+    let q_scale: f64 = d.abs() + 2.0 * (c * shift).abs() + 8.0 * (shift2 * shift).abs();
+    let mut roots: [HComplex; 4] = if q.abs() <= 64.0 * f64::EPSILON * q_scale {
+        let [z1, z2] = monic_quadratic_roots(HComplex::from(p), HComplex::from(r));
+        let (y1, y2): (HComplex, HComplex) = (z1.h_sqrt(), z2.h_sqrt());
+        [y1, -y1, y2, -y2]
+    } else {
+        // (y^2 + p/2 + m)^2 = 2m y^2 - q y + m^2 + mp + p^2/4 - r, and the right side is a perfect square
+        // when m is a root of the resolvent cubic m^3 + p m^2 + (p^2/4 - r) m - q^2/8 = 0.
+        // The resolvent is negative at 0 and grows without bound, so it has a positive real root.
+        let resolvent: [HComplex; 3] = h_solve_cubic(1.0, p, p * p / 4.0 - r, -q * q / 8.0)?;
+        let m: f64 = resolvent
+            .iter()
+            .filter(|root| root.is_real())
+            .map(|root| root.re())
+            .fold(f64::NEG_INFINITY, f64::max);
+        let s: f64 = (2.0 * m).sqrt();
+        let base: f64 = p / 2.0 + m;
+        let offset: f64 = q / (2.0 * s);
+        let [y1, y2] = monic_quadratic_roots(HComplex::from(-s), HComplex::from(base + offset));
+        let [y3, y4] = monic_quadratic_roots(HComplex::from(s), HComplex::from(base - offset));
+        [y1, y2, y3, y4]
+    };
+
+    for root in roots.iter_mut() {
+        *root = *root - shift;
+    }
+    sort_roots(&mut roots);
+    Some(roots)
+}
+
+
+/// Roots of `x^2 + bx + c = 0` with complex coefficients, using the stable form of the quadratic formula.
+fn monic_quadratic_roots(b: HComplex, c: HComplex) -> [HComplex; 2] {
+    let sqrt_discriminant: HComplex = (b * b - c * 4.0).h_sqrt();
+    // Pick the sign that makes b and the square root point the same way, so their sum does not cancel.
+    let sign: f64 = if b.re() * sqrt_discriminant.re() + b.im() * sqrt_discriminant.im() >= 0.0 { 1.0 } else { -1.0 };
+    let q: HComplex = (b + sqrt_discriminant * sign) * -0.5;
+    if q == HComplex::from(0.0) {
+        return [q, q];
+    }
+    [q, c / q]
+}
+
+
+/// Sorts roots with the real ones first in ascending order, then the complex ones by real part,
+/// with the positive imaginary part of a conjugate pair first.
+/// Each complex root is first paired with the root closest to its conjugate and both are made exactly
+/// conjugate, so rounding in the real parts cannot put the negative imaginary part of a pair first.
+fn sort_roots(roots: &mut [HComplex]) {
+    let mut paired: Vec<bool> = roots.iter().map(|root| root.is_real()).collect();
+    for i in 0..roots.len() {
+        if paired[i] {
+            continue;
+        }
+        paired[i] = true;
+        let conjugate: HComplex = HComplex::new(roots[i].re(), -roots[i].im());
+        let partner: Option<usize> = (i + 1..roots.len())
+            .filter(|&j| !paired[j])
+            .min_by(|&j, &k| (roots[j] - conjugate).h_abs().total_cmp(&(roots[k] - conjugate).h_abs()));
+        if let Some(j) = partner {
+            paired[j] = true;
+            let re: f64 = (roots[i].re() + roots[j].re()) / 2.0;
+            let im: f64 = (roots[i].im().abs() + roots[j].im().abs()) / 2.0;
+            roots[i] = HComplex::new(re, im);
+            roots[j] = HComplex::new(re, -im);
+        }
+    }
+    roots.sort_by(compare_roots);
+}
+
+
+/// The order used by `sort_roots`.
+fn compare_roots(left: &HComplex, right: &HComplex) -> Ordering {
+    right
+        .is_real()
+        .cmp(&left.is_real())
+        .then(left.re().total_cmp(&right.re()))
+        .then(right.im().total_cmp(&left.im()))
+}
+
+
+/// A polynomial with real coefficients, stored lowest degree first: `coefficients[i]` multiplies `x^i`.
+/// Trailing zero coefficients are removed, so the zero polynomial has no coefficients and two equal
+/// polynomials always compare equal. Supports `+`, `-` and `*` between polynomials (owned or borrowed),
+/// multiplication by a scalar, long division, evaluation, calculus and composition.
+///
+/// Example:
+///
+/// let p = HPolynomial::new(&[1, -2, 3]); // 3x^2 - 2x + 1
+/// assert_eq!(p.to_string(), "3x^2 - 2x + 1");
+/// assert_eq!(p.h_evaluate(2), 9.0);
+/// assert_eq!(p.h_derivative(), HPolynomial::new(&[-2, 6]));
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HPolynomial {
+    coefficients: Vec<f64>,
+}
+
+impl HPolynomial {
+    /// Creates a polynomial from its coefficients, lowest degree first.
+    /// Example: HPolynomial::new(&[1, 0, 2]) is 2x^2 + 1.
+    pub fn new<T>(coefficients: &[T]) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        Self::from_vec(coefficients.iter().map(|&c| c.into()).collect())
+    }
+
+    /// Creates the zero polynomial.
+    pub fn zero() -> Self {
+        HPolynomial { coefficients: Vec::new() }
+    }
+
+    /// Creates the constant polynomial `value`.
+    pub fn constant<T: Into<f64>>(value: T) -> Self {
+        Self::from_vec(vec![value.into()])
+    }
+
+    /// Creates the monomial `coefficient·x^degree`.
+    pub fn monomial<T: Into<f64>>(coefficient: T, degree: usize) -> Self {
+        let mut coefficients: Vec<f64> = vec![0.0; degree + 1];
+        coefficients[degree] = coefficient.into();
+        Self::from_vec(coefficients)
+    }
+
+    /// Creates the monic polynomial `(x - r1)(x - r2)...` with the given real roots.
+    /// Example: HPolynomial::from_roots(&[1, 2]) is x^2 - 3x + 2.
+    pub fn from_roots<T>(roots: &[T]) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        roots.iter().fold(Self::constant(1.0), |product, &root| {
+            &product * &Self::from_vec(vec![-root.into(), 1.0])
+        })
+    }
+
+    fn from_vec(mut coefficients: Vec<f64>) -> Self {
+        while coefficients.last() == Some(&0.0) {
+            coefficients.pop();
+        }
+        HPolynomial { coefficients }
+    }
+
+    /// Returns the coefficients, lowest degree first. The zero polynomial has none.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Returns the coefficient of `x^degree`, which is 0 above the degree of the polynomial.
+    pub fn coefficient(&self, degree: usize) -> f64 {
+        self.coefficients.get(degree).copied().unwrap_or(0.0)
+    }
+
+    /// Returns the degree, or None for the zero polynomial, whose degree is undefined.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Returns the coefficient of the highest power, or None for the zero polynomial.
+    pub fn leading_coefficient(&self) -> Option<f64> {
+        self.coefficients.last().copied()
+    }
+
+    /// Returns true for the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's scheme.
+    pub fn h_evaluate<T: Into<f64>>(&self, x: T) -> f64 {
+        let x: f64 = x.into();
+        self.coefficients.iter().rev().fold(0.0, |acc, &c| acc * x + c)
+    }
+
+    /// Evaluates the polynomial at a complex `z` with Horner's scheme.
+    pub fn h_evaluate_complex(&self, z: HComplex) -> HComplex {
+        self.coefficients.iter().rev().fold(HComplex::from(0.0), |acc, &c| acc * z + c)
+    }
+
+    /// Returns the derivative.
+    /// Example: the derivative of 3x^2 - 2x + 1 is 6x - 2.
+    pub fn h_derivative(&self) -> HPolynomial {
+        Self::from_vec(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, &c)| c * power as f64)
+                .collect(),
+        )
+    }
+
+    /// Returns the antiderivative whose value at 0 is `constant`.
+    /// Example: HPolynomial::new(&[-2, 6]).h_antiderivative(1) is 3x^2 - 2x + 1.
+    pub fn h_antiderivative<T: Into<f64>>(&self, constant: T) -> HPolynomial {
+        let mut coefficients: Vec<f64> = vec![constant.into()];
+        coefficients.extend(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .map(|(power, &c)| c / (power + 1) as f64),
+        );
+        Self::from_vec(coefficients)
+    }
+
+    /// Returns the composition `self(inner(x))`.
+    /// Example: composing x^2 + 1 with x - 1 gives x^2 - 2x + 2.
+    pub fn h_compose(&self, inner: &HPolynomial) -> HPolynomial {
+        self.coefficients.iter().rev().fold(Self::zero(), |acc, &c| {
+            &(&acc * inner) + &Self::constant(c)
+        })
+    }
+
+    /// Divides by `divisor` with polynomial long division and returns `(quotient, remainder)`,
+    /// where `self = quotient·divisor + remainder` and the remainder has a lower degree than the divisor.
+    /// Returns None when the divisor is the zero polynomial.
+    ///
+    /// Example:
+    ///
+    /// let (quotient, remainder) = HPolynomial::new(&[-4, 0, -2, 1]).h_div_rem(&HPolynomial::new(&[-3, 1])).unwrap();
+    /// assert_eq!(quotient, HPolynomial::new(&[3, 1, 1]));
+    /// assert_eq!(remainder, HPolynomial::constant(5));
+    pub fn h_div_rem(&self, divisor: &HPolynomial) -> Option<(HPolynomial, HPolynomial)> {
+        let leading: f64 = divisor.leading_coefficient()?;
+        let divisor_len: usize = divisor.coefficients.len();
+        if self.coefficients.len() < divisor_len {
+            return Some((Self::zero(), self.clone()));
+        }
+
+        let mut remainder: Vec<f64> = self.coefficients.clone();
+        let mut quotient: Vec<f64> = vec![0.0; remainder.len() - divisor_len + 1];
+        for k in (0..quotient.len()).rev() {
+            let factor: f64 = remainder[k + divisor_len - 1] / leading;
+            quotient[k] = factor;
+            for (r, &d) in remainder[k..k + divisor_len].iter_mut().zip(&divisor.coefficients) {
+                *r -= factor * d;
+            }
+        }
+        // The cancelled leading terms may hold rounding noise instead of exact zeros; they are dropped.
+        remainder.truncate(divisor_len - 1);
+        Some((Self::from_vec(quotient), Self::from_vec(remainder)))
+    }
+
+    /// Returns the monic greatest common divisor, computed with the Euclidean algorithm.
+    /// Because the coefficients are floating point, remainder coefficients smaller than `1e-9` times the largest
+    /// coefficient of the dividend are treated as zero. The gcd of two zero polynomials is the zero polynomial.
+    ///
+    /// Example:
+    ///
+    /// let p = HPolynomial::from_roots(&[1, 2]);
+    /// let q = HPolynomial::from_roots(&[2, 3]);
+    /// assert_eq!(p.h_gcd(&q), HPolynomial::from_roots(&[2]));
+    pub fn h_gcd(&self, other: &HPolynomial) -> HPolynomial {
+        let mut a: HPolynomial = self.clone();
+        let mut b: HPolynomial = other.clone();
+        while !b.is_zero() {
+            let scale: f64 = a.coefficients.iter().fold(0.0, |m: f64, c| m.max(c.abs()));
+            let (_, remainder) = a.h_div_rem(&b).unwrap();
+            let cleaned: Vec<f64> = remainder
+                .coefficients
+                .into_iter()
+                .map(|c| if c.abs() <= 1e-9 * scale { 0.0 } else { c })
+                .collect();
+            a = b;
+            b = Self::from_vec(cleaned);
+        }
+        match a.leading_coefficient() {
+            Some(leading) => a * (1.0 / leading),
+            None => a,
+        }
+    }
+
+    /// Finds all roots of the polynomial, real and complex, and groups repeated roots with their multiplicity.
+    /// Returns None for the zero polynomial, for which every number is a root, or if the iteration does not
+    /// converge. A nonzero constant has no roots.
+    ///
+    /// Roots at zero are factored out exactly. The other roots are found together with the Aberth–Ehrlich
+    /// iteration, which converges for any starting points in practice. Estimates that cannot be told apart
+    /// by evaluating the polynomial (its value along the segment between them is below the rounding error
+    /// of the evaluation) are merged into one root whose multiplicity is the number of estimates, placed at
+    /// their mean, which is far more accurate than any single estimate of a multiple root. The merge only happens
+    /// if the derivatives below that multiplicity vanish at the root as well; otherwise the noise merely covers
+    /// several distinct roots, as for the roots 1 to 20, and the estimates are refined as simple roots.
+    /// A root that cannot be told apart from its real part in the same way is made real.
+    /// Complex roots are returned as exact conjugate pairs, as they must be for real coefficients.
+    /// With `polish`, every root is then refined with Newton's method on the `(m - 1)`th derivative,
+    /// for which it is a simple root; real roots stay real during polishing.
+    /// Roots are sorted like those of `h_solve_cubic`.
+    ///
+    /// Example:
+    ///
+    /// let p = &HPolynomial::from_roots(&[1, 1, 3]) * &HPolynomial::new(&[1, 0, 1]); // (x - 1)^2 (x - 3) (x^2 + 1)
+    /// let roots = p.h_roots(true).unwrap();
+    /// roots[0] is approximately 1 with multiplicity 2, roots[1] is approximately 3 with multiplicity 1,
+    /// and roots[2] and roots[3] are approximately i and -i.
+    pub fn h_roots(&self, polish: bool) -> Option<Vec<HPolynomialRoot>> {
+        if self.is_zero() {
+            return None;
+        }
+        let zero_roots: usize = self.coefficients.iter().take_while(|&&c| c == 0.0).count();
+        let reduced: HPolynomial = HPolynomial::from_vec(self.coefficients[zero_roots..].to_vec());
+
+        let mut roots: Vec<HPolynomialRoot> = Vec::new();
+        if zero_roots > 0 {
+            roots.push(HPolynomialRoot { root: HComplex::from(0.0), multiplicity: zero_roots });
+        }
+
+        let mut estimates: Vec<HComplex> = reduced.aberth_estimates()?;
+        let all_estimates: Vec<HComplex> = estimates.clone();
+        while let Some(first) = estimates.pop() {
+            let mut cluster: Vec<HComplex> = vec![first];
+            estimates.retain(|&estimate| {
+                if reduced.is_negligible_between(first, estimate) {
+                    cluster.push(estimate);
+                    false
+                } else {
+                    true
+                }
+            });
+
+            // On a badly conditioned polynomial the rounding noise can span several distinct roots, so a cluster
+            // only counts as a multiple root if the derivatives below its multiplicity vanish there too.
+            // Otherwise its estimates are refined further as simple roots.
+            let mean: HComplex = cluster.iter().copied().sum::<HComplex>() / cluster.len() as f64;
+            let groups: Vec<(HComplex, usize)> = if reduced.is_multiple_root(mean, cluster.len()) {
+                vec![(mean, cluster.len())]
+            } else {
+                reduced.refine_simple_roots(&cluster, &all_estimates).into_iter().map(|estimate| (estimate, 1)).collect()
+            };
+            for (mut root, multiplicity) in groups {
+                if !root.is_real() && reduced.is_negligible_between(root, HComplex::from(root.re())) {
+                    root = HComplex::from(root.re());
+                }
+                if polish {
+                    root = reduced.newton_polish(root, multiplicity);
+                }
+                roots.push(HPolynomialRoot { root, multiplicity });
+            }
+        }
+
+        // The coefficients are real, so complex roots come in conjugate pairs; each pair is made exactly conjugate.
+        for i in 0..roots.len() {
+            if roots[i].root.im() <= 0.0 {
+                continue;
+            }
+            let target: HComplex = roots[i].root.h_conjugate();
+            let partner: Option<usize> = (0..roots.len())
+                .filter(|&j| roots[j].root.im() < 0.0 && roots[j].multiplicity == roots[i].multiplicity)
+                .min_by(|&a, &b| (roots[a].root - target).h_abs().total_cmp(&(roots[b].root - target).h_abs()));
+            if let Some(j) = partner {
+                let paired: HComplex = (roots[i].root + roots[j].root.h_conjugate()) * 0.5;
+                roots[i].root = paired;
+                roots[j].root = paired.h_conjugate();
+            }
+        }
+
+        roots.sort_by(|left, right| compare_roots(&left.root, &right.root));
+        Some(roots)
+    }
+
+    /// Simultaneous estimates of all roots with the Aberth–Ehrlich iteration. The constant coefficient must be nonzero.
+    fn aberth_estimates(&self) -> Option<Vec<HComplex>> {
+        let degree: usize = self.degree()?;
+        let leading: f64 = self.leading_coefficient()?;
+        // Fujiwara's bound: every root lies within this radius, so the estimates start on a circle around all of them.
+        // The constant coefficient enters the bound halved.
+        let radius: f64 = 2.0 * (0..degree)
+            .map(|i| {
+                let ratio: f64 = (self.coefficients[i] / leading).abs();
+                let ratio: f64 = if i == 0 { ratio / 2.0 } else { ratio };
+                ratio.powf(1.0 / (degree - i) as f64)
+            })
+            .fold(0.0, f64::max);
+        // The angular offset keeps the starting points off the real axis, where they could never become complex.
+        let mut estimates: Vec<HComplex> = (0..degree)
+            .map(|k| HComplex::from_polar(radius, 2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4))
+            .collect();
+        let derivative: HPolynomial = self.h_derivative();
+
+        for _ in 0..1000 {
+            let mut converged: bool = true;
+            for k in 0..degree {
+                if self.is_negligible_at(estimates[k]) {
+                    continue;
+                }
+                converged = false;
+                estimates[k] = self.aberth_step(&derivative, &estimates, k);
+            }
+            if converged {
+                return Some(estimates);
+            }
+        }
+        None
+    }
+
+    /// One Aberth–Ehrlich update of `estimates[k]`: a Newton step on the polynomial divided by the factors
+    /// of all other estimates, which keeps the estimates from converging to the same root.
+    fn aberth_step(&self, derivative: &HPolynomial, estimates: &[HComplex], k: usize) -> HComplex {
+        let z: HComplex = estimates[k];
+        let repulsion: HComplex = estimates
+            .iter()
+            .enumerate()
+            .filter(|&(j, &other)| j != k && other != z)
+            .map(|(_, &other)| (z - other).h_reciprocal())
+            .sum();
+        let denominator: HComplex = derivative.h_evaluate_complex(z) / self.h_evaluate_complex(z) - repulsion;
+        if denominator == HComplex::from(0.0) {
+            return z;
+        }
+        z - denominator.h_reciprocal()
+    }
+
+    /// Continues the Aberth–Ehrlich iteration for the estimates of `cluster` alone, treating them as simple roots
+    /// and keeping the rest of `estimates` fixed, until the updates fall below the rounding error of the estimates.
+    fn refine_simple_roots(&self, cluster: &[HComplex], estimates: &[HComplex]) -> Vec<HComplex> {
+        let derivative: HPolynomial = self.h_derivative();
+        let mut points: Vec<HComplex> = cluster.to_vec();
+        points.extend(estimates.iter().filter(|estimate| !cluster.contains(estimate)));
+        for _ in 0..100 {
+            let mut converged: bool = true;
+            for k in 0..cluster.len() {
+                let updated: HComplex = self.aberth_step(&derivative, &points, k);
+                if (updated - points[k]).h_abs() > 4.0 * f64::EPSILON * points[k].h_abs() {
+                    converged = false;
+                }
+                points[k] = updated;
+            }
+            if converged {
+                break;
+            }
+        }
+        points.truncate(cluster.len());
+        points
+    }
+
+    /// Returns true when the value at `z` is within the rounding error of evaluating the polynomial there,
+    /// so `z` is a root as far as floating point arithmetic can tell.
+    fn is_negligible_at(&self, z: HComplex) -> bool {
+        let magnitude: f64 = z.h_abs();
+        let bound: f64 = self.coefficients.iter().rev().fold(0.0, |acc, &c| acc * magnitude + c.abs());
+        self.h_evaluate_complex(z).h_abs() <= 4.0 * self.coefficients.len() as f64 * f64::EPSILON * bound
+    }
+
+    /// Returns true when the polynomial is negligible at every sampled point of the segment from `a` to `b`,
+    /// so the two points lie in the same region of rounding noise around a (multiple) root.
+    /// A single midpoint is not enough, since it may land on a third, distinct root. The sample positions are
+    /// multiples of the golden ratio modulo 1, so they do not line up with equally spaced roots either.
+    fn is_negligible_between(&self, a: HComplex, b: HComplex) -> bool {
+        let golden_ratio: f64 = (1.0 + 5f64.sqrt()) / 2.0;
+        (1..=8).all(|k| {
+            let t: f64 = (k as f64 * golden_ratio).fract();
+            self.is_negligible_at(a * (1.0 - t) + b * t)
+        })
+    }
+
+    /// Returns true when a root of multiplicity `multiplicity` lies near `z` as far as rounding allows. `z` is first
+    /// polished on the `(multiplicity - 1)`th derivative, and the polynomial and all its derivatives up to that one
+    /// must then be negligible at the polished point.
+    fn is_multiple_root(&self, z: HComplex, multiplicity: usize) -> bool {
+        let z: HComplex = self.newton_polish(z, multiplicity);
+        let mut derivative: HPolynomial = self.clone();
+        (0..multiplicity).all(|_| {
+            let negligible: bool = derivative.is_negligible_at(z);
+            derivative = derivative.h_derivative();
+            negligible
+        })
+    }
+
+    /// Refines a root of multiplicity `multiplicity` with Newton's method on the derivative of order
+    /// `multiplicity - 1`, stopping as soon as a step does not get smaller than the previous one.
+    fn newton_polish(&self, mut root: HComplex, multiplicity: usize) -> HComplex {
+        let mut target: HPolynomial = self.clone();
+        for _ in 1..multiplicity {
+            target = target.h_derivative();
+        }
+        let derivative: HPolynomial = target.h_derivative();
+        let mut last_step: f64 = f64::INFINITY;
+        for _ in 0..20 {
+            let slope: HComplex = derivative.h_evaluate_complex(root);
+            if slope == HComplex::from(0.0) {
+                break;
+            }
+            let step: HComplex = target.h_evaluate_complex(root) / slope;
+            if step.h_abs() >= last_step {
+                break;
+            }
+            root -= step;
+            last_step = step.h_abs();
+            if last_step == 0.0 {
+                break;
+            }
+        }
+        root
+    }
+}
+
+
+/// A root of a polynomial together with its multiplicity, returned by `HPolynomial::h_roots`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HPolynomialRoot {
+    root: HComplex,
+    multiplicity: usize,
+}
+
+impl HPolynomialRoot {
+    /// Returns the root.
+    pub fn root(&self) -> HComplex {
+        self.root
+    }
+
+    /// Returns how many times the root is repeated.
+    pub fn multiplicity(&self) -> usize {
+        self.multiplicity
+    }
+}
+
+impl fmt::Display for HPolynomial {
+    /// Formats the polynomial highest degree first, such as `3x^2 - 2x + 1`, honouring the precision if one is given.
+    /// Zero terms are left out and unit coefficients are not written; the zero polynomial is displayed as `0`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut first: bool = true;
+        for (power, &c) in self.coefficients.iter().enumerate().rev() {
+            if c == 0.0 {
+                continue;
+            }
+            let sign: &str = match (first, c < 0.0) {
+                (true, true) => "-",
+                (true, false) => "",
+                (false, true) => " - ",
+                (false, false) => " + ",
+            };
+            write!(f, "{}", sign)?;
+            if c.abs() != 1.0 || power == 0 {
+                match f.precision() {
+                    Some(precision) => write!(f, "{:.*}", precision, c.abs())?,
+                    None => write!(f, "{}", c.abs())?,
+                }
+            }
+            match power {
+                0 => {}
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{}", power)?,
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl Add for &HPolynomial {
+    type Output = HPolynomial;
+
+    fn add(self, other: &HPolynomial) -> HPolynomial {
+        let len: usize = self.coefficients.len().max(other.coefficients.len());
+        HPolynomial::from_vec((0..len).map(|i| self.coefficient(i) + other.coefficient(i)).collect())
+    }
+}
+
+impl Sub for &HPolynomial {
+    type Output = HPolynomial;
+
+    fn sub(self, other: &HPolynomial) -> HPolynomial {
+        let len: usize = self.coefficients.len().max(other.coefficients.len());
+        HPolynomial::from_vec((0..len).map(|i| self.coefficient(i) - other.coefficient(i)).collect())
+    }
+}
+
+impl Mul for &HPolynomial {
+    type Output = HPolynomial;
+
+    fn mul(self, other: &HPolynomial) -> HPolynomial {
+        if self.is_zero() || other.is_zero() {
+            return HPolynomial::zero();
+        }
+        let mut coefficients: Vec<f64> = vec![0.0; self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        HPolynomial::from_vec(coefficients)
+    }
+}
+
+impl Add for HPolynomial {
+    type Output = HPolynomial;
+
+    fn add(self, other: HPolynomial) -> HPolynomial {
+        &self + &other
+    }
+}
+
+impl Sub for HPolynomial {
+    type Output = HPolynomial;
+
+    fn sub(self, other: HPolynomial) -> HPolynomial {
+        &self - &other
+    }
+}
+
+impl Mul for HPolynomial {
+    type Output = HPolynomial;
+
+    fn mul(self, other: HPolynomial) -> HPolynomial {
+        &self * &other
+    }
+}
+
+impl Mul<f64> for HPolynomial {
+    type Output = HPolynomial;
+
+    fn mul(self, scalar: f64) -> HPolynomial {
+        HPolynomial::from_vec(self.coefficients.into_iter().map(|c| c * scalar).collect())
+    }
+}
+
+impl Neg for HPolynomial {
+    type Output = HPolynomial;
+
+    fn neg(self) -> HPolynomial {
+        self * -1.0
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_h_quadratic_equation() {
+        assert_eq!(h_quadratic_equation(1.0, -3.0, 2.0), (2.0, 1.0));
+    }
+
+    #[test]
+    fn test_h_solve_quadratic() {
+        assert_eq!(h_solve_quadratic(1, -3, 2), HQuadraticRoots::TwoReal(1.0, 2.0));
+        assert_eq!(h_solve_quadratic(-1, 3, -2), HQuadraticRoots::TwoReal(1.0, 2.0));
+        assert_eq!(h_solve_quadratic(2, 0, -8), HQuadraticRoots::TwoReal(-2.0, 2.0));
+        assert_eq!(h_solve_quadratic(1, -4, 0), HQuadraticRoots::TwoReal(0.0, 4.0));
+        assert_eq!(h_solve_quadratic(1, 2, 1), HQuadraticRoots::Repeated(-1.0));
+        assert_eq!(
+            h_solve_quadratic(1, 2, 5),
+            HQuadraticRoots::ComplexPair(HComplex::new(-1.0, 2.0), HComplex::new(-1.0, -2.0))
+        );
+        assert_eq!(
+            h_solve_quadratic(-1, 0, -4),
+            HQuadraticRoots::ComplexPair(HComplex::new(0.0, 2.0), HComplex::new(0.0, -2.0))
+        );
+    }
+
+    #[test]
+    fn test_h_solve_quadratic_degenerate() {
+        assert_eq!(h_solve_quadratic(0, 2, -4), HQuadraticRoots::Linear(2.0));
+        assert_eq!(h_solve_quadratic(0, 0, 3), HQuadraticRoots::NoSolution);
+        assert_eq!(h_solve_quadratic(0, 0, 0), HQuadraticRoots::InfiniteSolutions);
+        assert_eq!(h_solve_quadratic(0, 2, -4).real_roots(), vec![2.0]);
+        assert!(h_solve_quadratic(1, 0, 1).real_roots().is_empty());
+    }
+
+    #[test]
+    fn test_h_solve_quadratic_precision() {
+        // x^2 - 1e8 x + 1 = 0 has roots close to 1e-8 and 1e8. The textbook formula returns 7.45e-9 for the small one.
+        let roots = h_solve_quadratic(1.0, -1e8, 1.0).real_roots();
+        assert!((roots[0] - 1e-8).abs() / 1e-8 < 1e-15);
+        assert!((roots[1] - 1e8).abs() / 1e8 < 1e-15);
+
+        // b^2 overflows without scaling.
+        assert_eq!(h_solve_quadratic(1e300, -3e300, 2e300), HQuadraticRoots::TwoReal(1.0, 2.0));
+
+        // Scaling all coefficients by the largest one would underflow b and c to zero here.
+        let roots = h_solve_quadratic(1e308, 1e-300, -1e-300).real_roots();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] + 1e-304).abs() / 1e-304 < 1e-12);
+        assert!((roots[1] - 1e-304).abs() / 1e-304 < 1e-12);
+        let roots = h_solve_quadratic(1e-300, 1.0, 1e-300).real_roots();
+        assert!((roots[0] + 1e300).abs() / 1e300 < 1e-12);
+        assert!((roots[1] + 1e-300).abs() / 1e-300 < 1e-12);
+    }
+
+    fn assert_roots(roots: &[HComplex], expected: &[HComplex]) {
+        assert_eq!(roots.len(), expected.len());
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!(root.h_approx_eq(expected, 1e-9), "{} != {}", root, expected);
+        }
+    }
+
+    #[test]
+    fn test_h_solve_cubic() {
+        let real = |x: f64| HComplex::from(x);
+        assert_roots(&h_solve_cubic(1, -6, 11, -6).unwrap(), &[real(1.0), real(2.0), real(3.0)]);
+        assert_roots(&h_solve_cubic(2, -4, -22, 24).unwrap(), &[real(-3.0), real(1.0), real(4.0)]);
+        assert_roots(&h_solve_cubic(1, -4, 5, -2).unwrap(), &[real(1.0), real(1.0), real(2.0)]);
+        assert_roots(&h_solve_cubic(1, -3, 3, -1).unwrap(), &[real(1.0), real(1.0), real(1.0)]);
+        // (x - 2)(x^2 + 2x + 5)
+        assert_roots(
+            &h_solve_cubic(1, 0, 1, -10).unwrap(),
+            &[real(2.0), HComplex::new(-1.0, 2.0), HComplex::new(-1.0, -2.0)],
+        );
+        assert!(h_solve_cubic(0, 1, 2, 3).is_none());
+
+        // The roots differ by ten orders of magnitude: about 1 - 1e10 and -0.5 ± 0.866i.
+        let roots = h_solve_cubic(1e-10, 1, 1, 1).unwrap();
+        assert!(roots[0].is_real() && (roots[0].re() - (1.0 - 1e10)).abs() < 1e-5);
+        assert!(roots[1].h_approx_eq(&HComplex::new(-0.5, 3f64.sqrt() / 2.0), 1e-9));
+        assert!(roots[2].h_approx_eq(&HComplex::new(-0.5, -(3f64.sqrt() / 2.0)), 1e-9));
+
+        // Dividing by a tiny a would overflow b / a and its square.
+        let roots = h_solve_cubic(1e-300, 1, 1, 1).unwrap();
+        assert!(roots[0].is_real() && (roots[0].re() + 1e300).abs() / 1e300 < 1e-12);
+        assert!(roots[1].h_approx_eq(&HComplex::new(-0.5, 3f64.sqrt() / 2.0), 1e-9));
+        assert!(roots[2].h_approx_eq(&HComplex::new(-0.5, -(3f64.sqrt() / 2.0)), 1e-9));
+        let roots = h_solve_cubic(1, 1e200, 1e200, 0).unwrap();
+        assert_roots(&roots[1..], &[real(-1.0), real(0.0)]);
+        assert!((roots[0].re() + 1e200).abs() / 1e200 < 1e-12);
+
+        // NaN is passed on instead of turning into a root at zero.
+        for roots in [h_solve_cubic(1.0, f64::NAN, 0.0, 0.0).unwrap(), h_solve_cubic(f64::NAN, 1.0, 1.0, 1.0).unwrap()] {
+            assert!(roots.iter().all(|root| root.re().is_nan()));
+        }
+    }
+
+    #[test]
+    fn test_h_solve_quartic() {
+        let real = |x: f64| HComplex::from(x);
+        assert_roots(
+            &h_solve_quartic(1, -10, 35, -50, 24).unwrap(),
+            &[real(1.0), real(2.0), real(3.0), real(4.0)],
+        );
+        assert_roots(
+            &h_solve_quartic(1, 0, 0, 0, -1).unwrap(),
+            &[real(-1.0), real(1.0), HComplex::new(0.0, 1.0), HComplex::new(0.0, -1.0)],
+        );
+        assert_roots(
+            &h_solve_quartic(1, 0, -5, 0, 4).unwrap(),
+            &[real(-2.0), real(-1.0), real(1.0), real(2.0)],
+        );
+        // (x - 1)(x + 2)(x^2 + 2x + 5)
+        let roots = h_solve_quartic(1.0, 3.0, 5.0, 1.0, -10.0).unwrap();
+        assert_roots(&roots[..2], &[real(-2.0), real(1.0)]);
+        assert!(roots[2].h_approx_eq(&HComplex::new(-1.0, 2.0), 1e-9));
+        assert!(roots[3].h_approx_eq(&HComplex::new(-1.0, -2.0), 1e-9));
+        // (x^2 + 1)(x^2 + 4)
+        assert_roots(
+            &h_solve_quartic(1, 0, 5, 0, 4).unwrap(),
+            &[HComplex::new(0.0, 2.0), HComplex::new(0.0, 1.0), HComplex::new(0.0, -1.0), HComplex::new(0.0, -2.0)],
+        );
+        assert!(h_solve_quartic(0, 1, 2, 3, 4).is_none());
+
+        // Both pairs are complex, and each pair is exactly conjugate with the positive imaginary part first.
+        let roots = h_solve_quartic(1, 0, 0, 1, 1).unwrap();
+        for pair in roots.chunks(2) {
+            assert!(pair[0].im() > 0.0);
+            assert_eq!(pair[1], HComplex::new(pair[0].re(), -pair[0].im()));
+        }
+        assert!(roots[0].re() < roots[2].re());
+        for root in roots {
+            assert!((root.h_powi(4) + root + 1.0).h_abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_polynomial_arithmetic() {
+        let p = HPolynomial::new(&[1, -2, 3]);
+        let q = HPolynomial::new(&[-1, 1]);
+        assert_eq!(&p + &q, HPolynomial::new(&[0, -1, 3]));
+        assert_eq!(&p - &p, HPolynomial::zero());
+        assert_eq!(&p * &q, HPolynomial::new(&[-1, 3, -5, 3]));
+        assert_eq!(p.clone() * 2.0, HPolynomial::new(&[2, -4, 6]));
+        assert_eq!(-q, HPolynomial::new(&[1, -1]));
+        assert_eq!(HPolynomial::new(&[1.0, 2.0, 0.0, 0.0]).degree(), Some(1));
+        assert_eq!(HPolynomial::zero().degree(), None);
+        assert_eq!(HPolynomial::monomial(4, 3).coefficients(), &[0.0, 0.0, 0.0, 4.0]);
+        assert_eq!(HPolynomial::from_roots(&[1, 2]), HPolynomial::new(&[2, -3, 1]));
+    }
+
+    #[test]
+    fn test_polynomial_evaluation_and_calculus() {
+        let p = HPolynomial::new(&[1, -2, 3]);
+        assert_eq!(p.h_evaluate(2), 9.0);
+        assert_eq!(p.h_evaluate_complex(HComplex::i()), HComplex::new(-2.0, -2.0));
+        assert_eq!(p.h_derivative(), HPolynomial::new(&[-2, 6]));
+        assert_eq!(p.h_derivative().h_antiderivative(1), p);
+        assert_eq!(HPolynomial::constant(5).h_derivative(), HPolynomial::zero());
+        let composed = HPolynomial::new(&[1, 0, 1]).h_compose(&HPolynomial::new(&[-1, 1]));
+        assert_eq!(composed, HPolynomial::new(&[2, -2, 1]));
+    }
+
+    #[test]
+    fn test_polynomial_division_and_gcd() {
+        let dividend = HPolynomial::new(&[-4, 0, -2, 1]);
+        let divisor = HPolynomial::new(&[-3, 1]);
+        let (quotient, remainder) = dividend.h_div_rem(&divisor).unwrap();
+        assert_eq!(quotient, HPolynomial::new(&[3, 1, 1]));
+        assert_eq!(remainder, HPolynomial::constant(5));
+        assert_eq!(&(&quotient * &divisor) + &remainder, dividend);
+        assert!(dividend.h_div_rem(&HPolynomial::zero()).is_none());
+        assert_eq!(divisor.h_div_rem(&dividend), Some((HPolynomial::zero(), divisor.clone())));
+
+        let p = HPolynomial::from_roots(&[1, 2]) * 3.0;
+        let q = HPolynomial::from_roots(&[2, 3]);
+        assert_eq!(p.h_gcd(&q), HPolynomial::from_roots(&[2]));
+        assert_eq!(p.h_gcd(&HPolynomial::from_roots(&[5])), HPolynomial::constant(1));
+        assert_eq!(p.h_gcd(&HPolynomial::zero()), HPolynomial::from_roots(&[1, 2]));
+        assert_eq!(HPolynomial::zero().h_gcd(&HPolynomial::zero()), HPolynomial::zero());
+    }
+
+    #[test]
+    fn test_polynomial_display() {
+        assert_eq!(HPolynomial::new(&[1, -2, 3]).to_string(), "3x^2 - 2x + 1");
+        assert_eq!(HPolynomial::new(&[0, 1, 0, -1]).to_string(), "-x^3 + x");
+        assert_eq!(HPolynomial::new(&[-1.5]).to_string(), "-1.5");
+        assert_eq!(HPolynomial::zero().to_string(), "0");
+        assert_eq!(format!("{:.2}", HPolynomial::new(&[0.5, 1.0])), "x + 0.50");
+    }
+
+    #[test]
+    fn test_polynomial_roots() {
+        let roots = HPolynomial::from_roots(&[1, 2, 3, 4, 5]).h_roots(true).unwrap();
+        assert_eq!(roots.len(), 5);
+        for (root, expected) in roots.iter().zip(1..=5) {
+            assert!(root.root().h_approx_eq(&HComplex::from(expected as f64), 1e-12));
+            assert!(root.root().is_real());
+            assert_eq!(root.multiplicity(), 1);
+        }
+
+        // x^6 - 1 has the sixth roots of unity.
+        let roots = HPolynomial::new(&[-1, 0, 0, 0, 0, 0, 1]).h_roots(false).unwrap();
+        assert_eq!(roots.len(), 6);
+        assert!(roots[0].root().h_approx_eq(&HComplex::from(-1.0), 1e-12));
+        assert!(roots[1].root().h_approx_eq(&HComplex::from(1.0), 1e-12));
+        let half_sqrt3: f64 = 3f64.sqrt() / 2.0;
+        assert!(roots[2].root().h_approx_eq(&HComplex::new(-0.5, half_sqrt3), 1e-12));
+        assert!(roots[5].root().h_approx_eq(&HComplex::new(0.5, -half_sqrt3), 1e-12));
+
+        assert_eq!(HPolynomial::constant(3).h_roots(true), Some(Vec::new()));
+        assert!(HPolynomial::zero().h_roots(true).is_none());
+    }
+
+    #[test]
+    fn test_polynomial_roots_multiplicity() {
+        // (x - 1)^3 (x + 2)^2 (x^2 + 1)
+        let p = &HPolynomial::from_roots(&[1, 1, 1, -2, -2]) * &HPolynomial::new(&[1, 0, 1]);
+        let roots = p.h_roots(true).unwrap();
+        let found: Vec<(HComplex, usize)> = roots.iter().map(|r| (r.root(), r.multiplicity())).collect();
+        assert_eq!(found.len(), 4);
+        assert_eq!(found[0], (HComplex::from(-2.0), 2));
+        assert_eq!(found[1], (HComplex::from(1.0), 3));
+        assert!(found[2].0.h_approx_eq(&HComplex::i(), 1e-9) && found[2].1 == 1);
+        assert!(found[3].0.h_approx_eq(&-HComplex::i(), 1e-9) && found[3].1 == 1);
+
+        // (x - 1)^2 (x^2 - 2x + 2)^2: the complex roots 1 ± i must not be mistaken for the real root below them.
+        let p = &HPolynomial::from_roots(&[1, 1]) * &HPolynomial::new(&[4, -8, 8, -4, 1]);
+        let roots = p.h_roots(true).unwrap();
+        assert_eq!(roots.len(), 3);
+        assert!(roots[0].root().h_approx_eq(&HComplex::from(1.0), 1e-9) && roots[0].multiplicity() == 2);
+        assert!(roots[1].root().h_approx_eq(&HComplex::new(1.0, 1.0), 1e-9) && roots[1].multiplicity() == 2);
+        assert_eq!(roots[2].root(), roots[1].root().h_conjugate());
+
+        // Wilkinson's polynomial with roots 1 to 12 is badly conditioned, and its roots are equally spaced.
+        let roots = HPolynomial::from_roots(&(1..=12).collect::<Vec<i32>>()).h_roots(true).unwrap();
+        assert_eq!(roots.len(), 12);
+        for (root, expected) in roots.iter().zip(1..=12) {
+            assert!(root.root().h_approx_eq(&HComplex::from(expected as f64), 1e-6));
+        }
+
+        // With roots 1 to 20 the rounding noise covers several roots at once, but none of them is multiple.
+        let roots = HPolynomial::from_roots(&(1..=20).collect::<Vec<i32>>()).h_roots(true).unwrap();
+        assert_eq!(roots.len(), 20);
+        assert!(roots.iter().all(|root| root.multiplicity() == 1));
+        for (root, expected) in roots.iter().zip(1..=20) {
+            assert!(root.root().h_approx_eq(&HComplex::from(expected as f64), 1e-2), "{} != {}", root.root(), expected);
+        }
+
+        // x^3 (x - 2): the roots at zero are factored out exactly.
+        let roots = HPolynomial::new(&[0, 0, 0, -2, 1]).h_roots(false).unwrap();
+        assert_eq!(roots[0], HPolynomialRoot { root: HComplex::from(0.0), multiplicity: 3 });
+        assert!(roots[1].root().h_approx_eq(&HComplex::from(2.0), 1e-12));
+    }
+}
@@ -0,0 +1,425 @@
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+
+/// A complex number `re + im·i`, stored as two `f64`.
+/// It supports the usual arithmetic operators, also mixed with `f64`, and the elementary functions
+/// (`h_exp`, `h_ln`, `h_pow`, `h_sqrt`, trigonometric and hyperbolic functions), which return the principal value
+/// where the function is multivalued. Like `f64`, division by zero gives infinite or NaN parts instead of panicking.
+/// `HMatrix<HComplex>` can hold complex entries; see `HMatrix::to_complex`.
+///
+/// Example:
+///
+/// let z = HComplex::new(3.0, 4.0);
+/// assert_eq!(z.h_abs(), 5.0);
+/// assert_eq!(z * z.h_conjugate(), HComplex::from(25.0));
+/// assert_eq!(HComplex::from(-4.0).h_sqrt(), HComplex::new(0.0, 2.0));
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HComplex {
+    re: f64,
+    im: f64,
+}
+
+impl HComplex {
+    /// Creates the complex number `re + im·i`.
+    pub fn new(re: f64, im: f64) -> Self {
+        HComplex { re, im }
+    }
+
+    /// Creates the imaginary unit `i`.
+    pub fn i() -> Self {
+        HComplex::new(0.0, 1.0)
+    }
+
+    /// Creates a complex number from polar coordinates, `r·e^(iθ) = r(cos θ + i sin θ)`.
+    pub fn from_polar(r: f64, theta: f64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        HComplex::new(r * cos, r * sin)
+    }
+
+    /// Returns the real part.
+    pub fn re(&self) -> f64 {
+        self.re
+    }
+
+    /// Returns the imaginary part.
+    pub fn im(&self) -> f64 {
+        self.im
+    }
+
+    /// Returns `true` if the imaginary part is exactly zero.
+    pub fn is_real(&self) -> bool {
+        self.im == 0.0
+    }
+
+    /// Calculates the modulus (absolute value) `|z| = sqrt(re² + im²)`, without overflow for large parts.
+    pub fn h_abs(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Calculates the argument, the angle of `z` from the positive real axis, in `(-π, π]`.
+    pub fn h_arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    /// Returns the polar form `(r, θ)`, where `z = r·e^(iθ)`.
+    pub fn to_polar(&self) -> (f64, f64) {
+        (self.h_abs(), self.h_arg())
+    }
+
+    /// Returns the complex conjugate `re - im·i`.
+    pub fn h_conjugate(&self) -> HComplex {
+        HComplex::new(self.re, -self.im)
+    }
+
+    /// Returns the reciprocal `1 / z`.
+    pub fn h_reciprocal(&self) -> HComplex {
+        HComplex::from(1.0) / *self
+    }
+
+    /// Checks whether both parts differ from those of `other` by at most `tolerance`.
+    pub fn h_approx_eq(&self, other: &HComplex, tolerance: f64) -> bool {
+        (self.re - other.re).abs() <= tolerance && (self.im - other.im).abs() <= tolerance
+    }
+
+    /// Calculates `e^z = e^re (cos im + i sin im)`.
+    pub fn h_exp(&self) -> HComplex {
+        HComplex::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Calculates the principal natural logarithm `ln|z| + i·arg(z)`, whose imaginary part is in `(-π, π]`.
+    /// The logarithm of zero has a real part of negative infinity.
+    pub fn h_ln(&self) -> HComplex {
+        HComplex::new(self.h_abs().ln(), self.h_arg())
+    }
+
+    /// Calculates the principal square root, the root with a non-negative real part.
+    /// It is computed without subtracting nearly equal numbers, so small imaginary parts stay accurate.
+    ///
+    /// Example:
+    ///
+    /// assert_eq!(HComplex::new(3.0, 4.0).h_sqrt(), HComplex::new(2.0, 1.0));
+    pub fn h_sqrt(&self) -> HComplex {
+        if self.re == 0.0 && self.im == 0.0 {
+            return HComplex::new(0.0, self.im);
+        }
+        let t: f64 = ((self.re.abs() + self.h_abs()) / 2.0).sqrt();
+        if self.re >= 0.0 {
+            HComplex::new(t, self.im / (2.0 * t))
+        } else {
+            HComplex::new(self.im.abs() / (2.0 * t), t.copysign(self.im))
+        }
+    }
+
+    /// Raises `z` to a complex power with the principal value `e^(w ln z)`. Zero to any power is zero,
+    /// except that anything to the power zero is one.
+    pub fn h_pow(&self, exponent: HComplex) -> HComplex {
+        if exponent.re == 0.0 && exponent.im == 0.0 {
+            return HComplex::from(1.0);
+        }
+        if self.re == 0.0 && self.im == 0.0 {
+            return HComplex::default();
+        }
+        (exponent * self.h_ln()).h_exp()
+    }
+
+    /// Raises `z` to a real power, using the polar form `r^p·e^(ipθ)`.
+    pub fn h_powf(&self, exponent: f64) -> HComplex {
+        if exponent == 0.0 {
+            return HComplex::from(1.0);
+        }
+        let (r, theta) = self.to_polar();
+        HComplex::from_polar(r.powf(exponent), theta * exponent)
+    }
+
+    /// Raises `z` to an integer power by repeated squaring, which is exact for Gaussian integers
+    /// such as `(1 + i)^4 = -4`, unlike the polar form.
+    pub fn h_powi(&self, exponent: i32) -> HComplex {
+        let mut base: HComplex = if exponent < 0 { self.h_reciprocal() } else { *self };
+        let mut result: HComplex = HComplex::from(1.0);
+        let mut remaining: u32 = exponent.unsigned_abs();
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            remaining >>= 1;
+        }
+        result
+    }
+
+    /// Calculates the sine, `sin(re)cosh(im) + i·cos(re)sinh(im)`.
+    pub fn h_sin(&self) -> HComplex {
+        HComplex::new(self.re.sin() * self.im.cosh(), self.re.cos() * self.im.sinh())
+    }
+
+    /// Calculates the cosine, `cos(re)cosh(im) - i·sin(re)sinh(im)`.
+    pub fn h_cos(&self) -> HComplex {
+        HComplex::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+
+    /// Calculates the tangent, `sin(z) / cos(z)`.
+    pub fn h_tan(&self) -> HComplex {
+        self.h_sin() / self.h_cos()
+    }
+
+    /// Calculates the hyperbolic sine, `sinh(re)cos(im) + i·cosh(re)sin(im)`.
+    pub fn h_sinh(&self) -> HComplex {
+        HComplex::new(self.re.sinh() * self.im.cos(), self.re.cosh() * self.im.sin())
+    }
+
+    /// Calculates the hyperbolic cosine, `cosh(re)cos(im) + i·sinh(re)sin(im)`.
+    pub fn h_cosh(&self) -> HComplex {
+        HComplex::new(self.re.cosh() * self.im.cos(), self.re.sinh() * self.im.sin())
+    }
+
+    /// Calculates the hyperbolic tangent, `sinh(z) / cosh(z)`.
+    pub fn h_tanh(&self) -> HComplex {
+        self.h_sinh() / self.h_cosh()
+    }
+}
+
+impl From<f64> for HComplex {
+    fn from(re: f64) -> Self {
+        HComplex::new(re, 0.0)
+    }
+}
+
+impl fmt::Display for HComplex {
+    /// Formats the number as `re + im i` or `re - im i`, honouring the precision if one is given.
+    /// For example `HComplex::new(1.5, -2.0)` is displayed as `1.5 - 2i`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign: char = if self.im.is_sign_negative() { '-' } else { '+' };
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} {} {:.*}i", precision, self.re, sign, precision, self.im.abs()),
+            None => write!(f, "{} {} {}i", self.re, sign, self.im.abs()),
+        }
+    }
+}
+
+impl Add for HComplex {
+    type Output = HComplex;
+
+    fn add(self, other: HComplex) -> HComplex {
+        HComplex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for HComplex {
+    type Output = HComplex;
+
+    fn sub(self, other: HComplex) -> HComplex {
+        HComplex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for HComplex {
+    type Output = HComplex;
+
+    fn mul(self, other: HComplex) -> HComplex {
+        HComplex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for HComplex {
+    type Output = HComplex;
+
+    /// Divides with Smith's algorithm, which scales by the larger part of the divisor
+    /// so that `|other|²` is never formed and cannot overflow.
+    fn div(self, other: HComplex) -> HComplex {
+        if other.re.abs() >= other.im.abs() {
+            let ratio: f64 = other.im / other.re;
+            let denominator: f64 = other.re + other.im * ratio;
+            HComplex::new(
+                (self.re + self.im * ratio) / denominator,
+                (self.im - self.re * ratio) / denominator,
+            )
+        } else {
+            let ratio: f64 = other.re / other.im;
+            let denominator: f64 = other.re * ratio + other.im;
+            HComplex::new(
+                (self.re * ratio + self.im) / denominator,
+                (self.im * ratio - self.re) / denominator,
+            )
+        }
+    }
+}
+
+impl Neg for HComplex {
+    type Output = HComplex;
+
+    fn neg(self) -> HComplex {
+        HComplex::new(-self.re, -self.im)
+    }
+}
+
+// Mixed arithmetic with `f64`, so that `2.0 * z` and `z + 1.0` work without converting first.
+
+impl Add<f64> for HComplex {
+    type Output = HComplex;
+
+    fn add(self, other: f64) -> HComplex {
+        HComplex::new(self.re + other, self.im)
+    }
+}
+
+impl Sub<f64> for HComplex {
+    type Output = HComplex;
+
+    fn sub(self, other: f64) -> HComplex {
+        HComplex::new(self.re - other, self.im)
+    }
+}
+
+impl Mul<f64> for HComplex {
+    type Output = HComplex;
+
+    fn mul(self, other: f64) -> HComplex {
+        HComplex::new(self.re * other, self.im * other)
+    }
+}
+
+impl Div<f64> for HComplex {
+    type Output = HComplex;
+
+    fn div(self, other: f64) -> HComplex {
+        HComplex::new(self.re / other, self.im / other)
+    }
+}
+
+impl Add<HComplex> for f64 {
+    type Output = HComplex;
+
+    fn add(self, other: HComplex) -> HComplex {
+        other + self
+    }
+}
+
+impl Sub<HComplex> for f64 {
+    type Output = HComplex;
+
+    fn sub(self, other: HComplex) -> HComplex {
+        HComplex::new(self - other.re, -other.im)
+    }
+}
+
+impl Mul<HComplex> for f64 {
+    type Output = HComplex;
+
+    fn mul(self, other: HComplex) -> HComplex {
+        other * self
+    }
+}
+
+impl Div<HComplex> for f64 {
+    type Output = HComplex;
+
+    fn div(self, other: HComplex) -> HComplex {
+        HComplex::from(self) / other
+    }
+}
+
+impl AddAssign for HComplex {
+    fn add_assign(&mut self, other: HComplex) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for HComplex {
+    fn sub_assign(&mut self, other: HComplex) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for HComplex {
+    fn mul_assign(&mut self, other: HComplex) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for HComplex {
+    fn div_assign(&mut self, other: HComplex) {
+        *self = *self / other;
+    }
+}
+
+impl Sum for HComplex {
+    fn sum<I: Iterator<Item = HComplex>>(iter: I) -> Self {
+        iter.fold(HComplex::default(), |acc, z| acc + z)
+    }
+}
+
+impl Product for HComplex {
+    fn product<I: Iterator<Item = HComplex>>(iter: I) -> Self {
+        iter.fold(HComplex::from(1.0), |acc, z| acc * z)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let a = HComplex::new(1.0, 2.0);
+        let b = HComplex::new(3.0, -1.0);
+        assert_eq!(a + b, HComplex::new(4.0, 1.0));
+        assert_eq!(a - b, HComplex::new(-2.0, 3.0));
+        assert_eq!(a * b, HComplex::new(5.0, 5.0));
+        assert!((a / b).h_approx_eq(&HComplex::new(0.1, 0.7), 1e-15));
+        assert_eq!((a / b) * b, a);
+        assert_eq!(-a, HComplex::new(-1.0, -2.0));
+        assert_eq!(2.0 * a + 1.0, HComplex::new(3.0, 4.0));
+        assert_eq!(1.0 - a, HComplex::new(0.0, -2.0));
+        assert_eq!(HComplex::i() * HComplex::i(), HComplex::from(-1.0));
+        assert_eq!([a, b, a].iter().copied().sum::<HComplex>(), HComplex::new(5.0, 3.0));
+        assert_eq!(format!("{}", HComplex::new(1.5, -2.0)), "1.5 - 2i");
+        assert_eq!(format!("{:.2}", a), "1.00 + 2.00i");
+    }
+
+    #[test]
+    fn test_complex_polar() {
+        let z = HComplex::new(-1.0, 1.0);
+        let (r, theta) = z.to_polar();
+        assert!((r - 2f64.sqrt()).abs() < 1e-15);
+        assert!((theta - 3.0 * std::f64::consts::FRAC_PI_4).abs() < 1e-15);
+        assert!(HComplex::from_polar(r, theta).h_approx_eq(&z, 1e-15));
+        assert_eq!(HComplex::new(3.0, 4.0).h_abs(), 5.0);
+        assert_eq!(HComplex::from(-1.0).h_arg(), std::f64::consts::PI);
+    }
+
+    #[test]
+    fn test_complex_functions() {
+        // Euler's identity: e^(iπ) + 1 = 0.
+        let euler = (HComplex::i() * std::f64::consts::PI).h_exp() + 1.0;
+        assert!(euler.h_approx_eq(&HComplex::default(), 1e-15));
+
+        let z = HComplex::new(0.5, -1.5);
+        assert!(z.h_ln().h_exp().h_approx_eq(&z, 1e-14));
+        assert!(z.h_sqrt().h_powi(2).h_approx_eq(&z, 1e-14));
+        assert_eq!(HComplex::new(3.0, 4.0).h_sqrt(), HComplex::new(2.0, 1.0));
+        assert_eq!(HComplex::new(-3.0, -4.0).h_sqrt(), HComplex::new(1.0, -2.0));
+        assert_eq!(HComplex::new(1.0, 1.0).h_powi(4), HComplex::from(-4.0));
+        assert!(z.h_powi(-2).h_approx_eq(&(HComplex::from(1.0) / (z * z)), 1e-14));
+        assert!(z.h_powf(3.0).h_approx_eq(&z.h_powi(3), 1e-13));
+
+        // i^i = e^(-π/2) is real.
+        let i_to_the_i = HComplex::i().h_pow(HComplex::i());
+        assert!(i_to_the_i.h_approx_eq(&HComplex::from((-std::f64::consts::FRAC_PI_2).exp()), 1e-15));
+
+        // sin² + cos² = 1 and cosh² - sinh² = 1 hold for complex arguments too.
+        let identity = z.h_sin() * z.h_sin() + z.h_cos() * z.h_cos();
+        assert!(identity.h_approx_eq(&HComplex::from(1.0), 1e-13));
+        let hyperbolic = z.h_cosh() * z.h_cosh() - z.h_sinh() * z.h_sinh();
+        assert!(hyperbolic.h_approx_eq(&HComplex::from(1.0), 1e-13));
+        assert!(z.h_tan().h_approx_eq(&(z.h_sin() / z.h_cos()), 1e-15));
+        assert!((HComplex::i() * z).h_sin().h_approx_eq(&(HComplex::i() * z.h_sinh()), 1e-14));
+        assert!(z.h_tanh().h_approx_eq(&(z.h_sinh() / z.h_cosh()), 1e-15));
+    }
+}
@@ -0,0 +1,1032 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+
+// --------------------------------- Expressions ------------------------------
+
+
+/// Describes why a formula could not be parsed by `HExpression::parse`.
+/// Every variant carries the position of the problem, counted in characters from the start of the formula.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A character that is not part of any number, name, operator or parenthesis.
+    UnexpectedCharacter { character: char, position: usize },
+    /// Digits that do not form a valid number, such as `1.2.3`.
+    InvalidNumber { text: String, position: usize },
+    /// A token in a place where it is not allowed, such as the second `*` in `2 * * 3`.
+    UnexpectedToken { token: String, position: usize },
+    /// The formula ended where an operand was still expected, such as after `2 +`. The position is the formula length.
+    UnexpectedEnd { position: usize },
+    /// A name followed by `(` that is not one of the supported functions.
+    UnknownFunction { name: String, position: usize },
+    /// A `(` that is never closed. The position is that of the `(`.
+    UnclosedParenthesis { position: usize },
+    /// Parentheses, signs, functions or operators are nested more than `MAX_NESTING_DEPTH` levels deep.
+    /// The position is that of the token that goes one level too deep.
+    TooDeeplyNested { position: usize },
+}
+
+impl ParseError {
+    /// Returns the position of the problem, counted in characters from the start of the formula.
+    pub fn position(&self) -> usize {
+        match self {
+            ParseError::UnexpectedCharacter { position, .. }
+            | ParseError::InvalidNumber { position, .. }
+            | ParseError::UnexpectedToken { position, .. }
+            | ParseError::UnexpectedEnd { position }
+            | ParseError::UnknownFunction { position, .. }
+            | ParseError::UnclosedParenthesis { position }
+            | ParseError::TooDeeplyNested { position } => *position,
+        }
+    }
+}
+
+
+/// Describes why an `HExpression` could not be evaluated by `HExpression::h_evaluate`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EvaluationError {
+    /// The expression uses a variable that has no value in the variable map.
+    UnknownVariable { name: String },
+}
+
+
+/// The functions of one argument that an `HExpression` can call, written as `name(argument)` in a formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HFunction {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    /// The natural logarithm.
+    Ln,
+    /// The base 10 logarithm.
+    Log,
+    Sqrt,
+    Abs,
+}
+
+impl HFunction {
+    const ALL: [HFunction; 14] = [
+        HFunction::Sin,
+        HFunction::Cos,
+        HFunction::Tan,
+        HFunction::Asin,
+        HFunction::Acos,
+        HFunction::Atan,
+        HFunction::Sinh,
+        HFunction::Cosh,
+        HFunction::Tanh,
+        HFunction::Exp,
+        HFunction::Ln,
+        HFunction::Log,
+        HFunction::Sqrt,
+        HFunction::Abs,
+    ];
+
+    /// Returns the name used for the function in formulas, such as `"sin"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HFunction::Sin => "sin",
+            HFunction::Cos => "cos",
+            HFunction::Tan => "tan",
+            HFunction::Asin => "asin",
+            HFunction::Acos => "acos",
+            HFunction::Atan => "atan",
+            HFunction::Sinh => "sinh",
+            HFunction::Cosh => "cosh",
+            HFunction::Tanh => "tanh",
+            HFunction::Exp => "exp",
+            HFunction::Ln => "ln",
+            HFunction::Log => "log",
+            HFunction::Sqrt => "sqrt",
+            HFunction::Abs => "abs",
+        }
+    }
+
+    /// Returns the function with the given formula name, or None if there is no such function.
+    pub fn from_name(name: &str) -> Option<HFunction> {
+        HFunction::ALL.into_iter().find(|function| function.name() == name)
+    }
+
+    /// Applies the function to `x`. Arguments outside the domain give NaN or infinity, like the `f64` methods.
+    pub fn h_apply(&self, x: f64) -> f64 {
+        match self {
+            HFunction::Sin => x.sin(),
+            HFunction::Cos => x.cos(),
+            HFunction::Tan => x.tan(),
+            HFunction::Asin => x.asin(),
+            HFunction::Acos => x.acos(),
+            HFunction::Atan => x.atan(),
+            HFunction::Sinh => x.sinh(),
+            HFunction::Cosh => x.cosh(),
+            HFunction::Tanh => x.tanh(),
+            HFunction::Exp => x.exp(),
+            HFunction::Ln => x.ln(),
+            HFunction::Log => x.log10(),
+            HFunction::Sqrt => x.sqrt(),
+            HFunction::Abs => x.abs(),
+        }
+    }
+}
+
+
+/// A parsed mathematical formula, stored as a syntax tree.
+///
+/// Formulas are written in the usual infix notation:
+/// - numbers such as `2`, `0.5` and `1.5e-3`, and variables such as `x` or `speed_2`
+/// - the constants `pi`, `tau` and `e`, which are replaced by their values while parsing,
+///   so they cannot be used as variable names
+/// - the operators `+`, `-`, `*`, `/` and `^`, with the usual precedence; `^` is right associative
+///   and binds tighter than a leading minus, so `-x^2` is `-(x^2)` and `2^3^2` is `2^(3^2)`
+/// - parentheses and the functions of `HFunction`, such as `sin(x)` and `sqrt(x + 1)`
+///
+/// Example:
+///
+/// let expression = HExpression::parse("3*x^2 + sin(y)/2").unwrap();
+/// let variables = HashMap::from([("x", 2.0), ("y", 0.0)]);
+/// assert_eq!(expression.h_evaluate(&variables), Ok(12.0));
+/// assert_eq!(expression.to_string(), "3*x^2 + sin(y)/2");
+#[derive(Debug, Clone, PartialEq)]
+pub enum HExpression {
+    Number(f64),
+    Variable(String),
+    Neg(Box<HExpression>),
+    Add(Box<HExpression>, Box<HExpression>),
+    Sub(Box<HExpression>, Box<HExpression>),
+    Mul(Box<HExpression>, Box<HExpression>),
+    Div(Box<HExpression>, Box<HExpression>),
+    Pow(Box<HExpression>, Box<HExpression>),
+    Function(HFunction, Box<HExpression>),
+}
+
+impl HExpression {
+    /// Parses a formula into an expression, or returns a `ParseError` with the position of the first problem.
+    ///
+    /// Example:
+    ///
+    /// assert!(HExpression::parse("2 * (x + 1)").is_ok());
+    /// assert_eq!(HExpression::parse("2 * (x + 1"), Err(ParseError::UnclosedParenthesis { position: 4 }));
+    pub fn parse(formula: &str) -> Result<HExpression, ParseError> {
+        let tokens: Vec<(Token, usize)> = tokenize(formula)?;
+        let mut parser: Parser = Parser { tokens, index: 0, depth: 0 };
+        let (expression, _) = parser.parse_expression(0)?;
+        match parser.next() {
+            (Token::End, _) => Ok(expression),
+            (token, position) => Err(ParseError::UnexpectedToken { token: token.to_string(), position }),
+        }
+    }
+
+    /// Evaluates the expression with the given values for its variables.
+    /// The map can have `&str` or `String` keys. Division by zero and functions outside their domain
+    /// give infinity or NaN, like `f64` arithmetic.
+    ///
+    /// Example:
+    ///
+    /// let expression = HExpression::parse("x / y").unwrap();
+    /// assert_eq!(expression.h_evaluate(&HashMap::from([("x", 1.0), ("y", 4.0)])), Ok(0.25));
+    /// assert_eq!(
+    ///     expression.h_evaluate(&HashMap::from([("x", 1.0)])),
+    ///     Err(EvaluationError::UnknownVariable { name: "y".to_string() })
+    /// );
+    pub fn h_evaluate<K>(&self, variables: &HashMap<K, f64>) -> Result<f64, EvaluationError>
+    where
+        K: Borrow<str> + Eq + Hash,
+    {
+        Ok(match self {
+            HExpression::Number(value) => *value,
+            HExpression::Variable(name) => *variables
+                .get(name.as_str())
+                .ok_or_else(|| EvaluationError::UnknownVariable { name: name.clone() })?,
+            HExpression::Neg(operand) => -operand.h_evaluate(variables)?,
+            HExpression::Add(left, right) => left.h_evaluate(variables)? + right.h_evaluate(variables)?,
+            HExpression::Sub(left, right) => left.h_evaluate(variables)? - right.h_evaluate(variables)?,
+            HExpression::Mul(left, right) => left.h_evaluate(variables)? * right.h_evaluate(variables)?,
+            HExpression::Div(left, right) => left.h_evaluate(variables)? / right.h_evaluate(variables)?,
+            HExpression::Pow(base, exponent) => base.h_evaluate(variables)?.powf(exponent.h_evaluate(variables)?),
+            HExpression::Function(function, argument) => function.h_apply(argument.h_evaluate(variables)?),
+        })
+    }
+
+    /// Returns the names of the variables used in the expression, sorted and without duplicates.
+    pub fn variables(&self) -> Vec<String> {
+        let mut names: BTreeSet<String> = BTreeSet::new();
+        self.collect_variables(&mut names);
+        names.into_iter().collect()
+    }
+
+    fn collect_variables(&self, names: &mut BTreeSet<String>) {
+        match self {
+            HExpression::Number(_) => {}
+            HExpression::Variable(name) => {
+                names.insert(name.clone());
+            }
+            HExpression::Neg(operand) | HExpression::Function(_, operand) => operand.collect_variables(names),
+            HExpression::Add(left, right)
+            | HExpression::Sub(left, right)
+            | HExpression::Mul(left, right)
+            | HExpression::Div(left, right)
+            | HExpression::Pow(left, right) => {
+                left.collect_variables(names);
+                right.collect_variables(names);
+            }
+        }
+    }
+
+    /// Returns the derivative with respect to `variable`, simplified with `h_simplify`.
+    /// Applies the sum, product, quotient, power and chain rules, and knows the derivative of every `HFunction`.
+    /// Other variables are treated as constants, so this is the partial derivative.
+    /// A power `a^b` whose exponent depends on the variable is differentiated as `e^(b·ln(a))`.
+    ///
+    /// Example:
+    ///
+    /// let expression = HExpression::parse("3*x^2 + sin(y)/2").unwrap();
+    /// assert_eq!(expression.h_derivative("x").to_string(), "6*x");
+    /// assert_eq!(expression.h_derivative("y").to_string(), "0.5*cos(y)");
+    pub fn h_derivative(&self, variable: &str) -> HExpression {
+        self.differentiate(variable).h_simplify()
+    }
+
+    /// Returns the partial derivatives with respect to each of `variables`, in the same order.
+    /// This is the gradient used to train models by gradient descent; see `Perceptron::net_input_expression`.
+    ///
+    /// Example:
+    ///
+    /// let gradient = HExpression::parse("x^2*y").unwrap().h_gradient(&["x", "y"]);
+    /// gradient[0] is 2*x*y and gradient[1] is x^2.
+    pub fn h_gradient<S: AsRef<str>>(&self, variables: &[S]) -> Vec<HExpression> {
+        variables.iter().map(|variable| self.h_derivative(variable.as_ref())).collect()
+    }
+
+    /// Returns true if the expression uses the variable `name`.
+    pub fn contains_variable(&self, name: &str) -> bool {
+        match self {
+            HExpression::Number(_) => false,
+            HExpression::Variable(variable) => variable == name,
+            HExpression::Neg(operand) | HExpression::Function(_, operand) => operand.contains_variable(name),
+            HExpression::Add(left, right)
+            | HExpression::Sub(left, right)
+            | HExpression::Mul(left, right)
+            | HExpression::Div(left, right)
+            | HExpression::Pow(left, right) => left.contains_variable(name) || right.contains_variable(name),
+        }
+    }
+
+    /// The derivative before simplification.
+    fn differentiate(&self, variable: &str) -> HExpression {
+        match self {
+            HExpression::Number(_) => number(0.0),
+            HExpression::Variable(name) => number(if name == variable { 1.0 } else { 0.0 }),
+            HExpression::Neg(operand) => neg(operand.differentiate(variable)),
+            HExpression::Add(left, right) => add(left.differentiate(variable), right.differentiate(variable)),
+            HExpression::Sub(left, right) => sub(left.differentiate(variable), right.differentiate(variable)),
+            HExpression::Mul(left, right) => add(
+                mul(left.differentiate(variable), (**right).clone()),
+                mul((**left).clone(), right.differentiate(variable)),
+            ),
+            HExpression::Div(left, right) => div(
+                sub(
+                    mul(left.differentiate(variable), (**right).clone()),
+                    mul((**left).clone(), right.differentiate(variable)),
+                ),
+                pow((**right).clone(), number(2.0)),
+            ),
+            HExpression::Pow(base, exponent) => {
+                let (base, exponent): (HExpression, HExpression) = ((**base).clone(), (**exponent).clone());
+                if !exponent.contains_variable(variable) {
+                    // d/dx a^n = n·a^(n - 1)·a'
+                    mul(
+                        mul(exponent.clone(), pow(base.clone(), sub(exponent, number(1.0)))),
+                        base.differentiate(variable),
+                    )
+                } else {
+                    // d/dx a^b = a^b·(b'·ln(a) + b·a'/a)
+                    mul(
+                        pow(base.clone(), exponent.clone()),
+                        add(
+                            mul(exponent.differentiate(variable), function(HFunction::Ln, base.clone())),
+                            div(mul(exponent, base.differentiate(variable)), base),
+                        ),
+                    )
+                }
+            }
+            HExpression::Function(f, argument) => {
+                let a: HExpression = (**argument).clone();
+                let outer: HExpression = match f {
+                    HFunction::Sin => function(HFunction::Cos, a),
+                    HFunction::Cos => neg(function(HFunction::Sin, a)),
+                    HFunction::Tan => add(number(1.0), pow(function(HFunction::Tan, a), number(2.0))),
+                    HFunction::Asin => div(number(1.0), function(HFunction::Sqrt, sub(number(1.0), pow(a, number(2.0))))),
+                    HFunction::Acos => neg(div(number(1.0), function(HFunction::Sqrt, sub(number(1.0), pow(a, number(2.0)))))),
+                    HFunction::Atan => div(number(1.0), add(number(1.0), pow(a, number(2.0)))),
+                    HFunction::Sinh => function(HFunction::Cosh, a),
+                    HFunction::Cosh => function(HFunction::Sinh, a),
+                    HFunction::Tanh => sub(number(1.0), pow(function(HFunction::Tanh, a), number(2.0))),
+                    HFunction::Exp => function(HFunction::Exp, a),
+                    HFunction::Ln => div(number(1.0), a),
+                    HFunction::Log => div(number(1.0), mul(a, number(std::f64::consts::LN_10))),
+                    HFunction::Sqrt => div(number(1.0), mul(number(2.0), function(HFunction::Sqrt, a))),
+                    HFunction::Abs => div(a.clone(), function(HFunction::Abs, a)),
+                };
+                mul(outer, argument.differentiate(variable))
+            }
+        }
+    }
+
+    /// Returns an algebraically simplified copy of the expression. The simplifier
+    /// - folds constants, such as `2 + 3*4` to `14` and `sqrt(16)` to `4`
+    /// - removes identities, such as `x + 0`, `1*x`, `x^1`, `x^0` and `-(-x)`, and turns `0*x` into `0`
+    /// - collects like terms in sums, so `x + 2*x - y` becomes `3*x - y`, and `x - x` becomes `0`
+    /// - collects equal factors in products, so `x*x^2/x` becomes `x^2` and `x/x` becomes `1`
+    ///
+    /// Factors of a product are sorted by how they are displayed, so equal products are recognised whatever their order;
+    /// the terms of a sum keep their order of appearance, with the constant last.
+    /// Like the usual computer algebra rules, `0*x` and `x/x` are simplified even though they are undefined
+    /// where `x` is infinite or zero.
+    ///
+    /// Example:
+    ///
+    /// let expression = HExpression::parse("2*x*3 + 0*y + x^1 - 4/2").unwrap();
+    /// assert_eq!(expression.h_simplify().to_string(), "7*x - 2");
+    pub fn h_simplify(&self) -> HExpression {
+        let mut current: HExpression = self.simplify_once();
+        // A pass can expose new simplifications, such as exponents that cancel after terms were collected.
+        for _ in 0..8 {
+            let next: HExpression = current.simplify_once();
+            if next == current {
+                break;
+            }
+            current = next;
+        }
+        current
+    }
+
+    fn simplify_once(&self) -> HExpression {
+        match self {
+            HExpression::Number(_) | HExpression::Variable(_) => self.clone(),
+            HExpression::Function(f, argument) => match argument.simplify_once() {
+                HExpression::Number(value) => number(f.h_apply(value)),
+                argument => function(*f, argument),
+            },
+            HExpression::Pow(base, exponent) => {
+                let (base, exponent): (HExpression, HExpression) = (base.simplify_once(), exponent.simplify_once());
+                match (&base, &exponent) {
+                    (HExpression::Number(b), HExpression::Number(e)) => number(b.powf(*e)),
+                    (_, HExpression::Number(e)) if *e == 0.0 => number(1.0),
+                    (_, HExpression::Number(e)) if *e == 1.0 => base,
+                    (HExpression::Number(b), _) if *b == 1.0 => number(1.0),
+                    _ => simplify_product(&pow(base, exponent)),
+                }
+            }
+            HExpression::Mul(left, right) => simplify_product(&mul(left.simplify_once(), right.simplify_once())),
+            HExpression::Div(left, right) => simplify_product(&div(left.simplify_once(), right.simplify_once())),
+            HExpression::Neg(operand) => simplify_sum(&neg(operand.simplify_once())),
+            HExpression::Add(left, right) => simplify_sum(&add(left.simplify_once(), right.simplify_once())),
+            HExpression::Sub(left, right) => simplify_sum(&sub(left.simplify_once(), right.simplify_once())),
+        }
+    }
+
+    /// How tightly the expression binds when displayed; operands that bind less tightly than their
+    /// operator requires are put in parentheses.
+    fn precedence(&self) -> u8 {
+        match self {
+            HExpression::Add(..) | HExpression::Sub(..) => 1,
+            HExpression::Mul(..) | HExpression::Div(..) => 2,
+            HExpression::Neg(_) => 3,
+            HExpression::Number(value) if value.is_sign_negative() => 3,
+            HExpression::Pow(..) => 4,
+            HExpression::Number(_) | HExpression::Variable(_) | HExpression::Function(..) => 5,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, min_precedence: u8) -> fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+
+    fn fmt_binary(f: &mut fmt::Formatter<'_>, left: &HExpression, operator: &str, right: &HExpression, precedence: u8) -> fmt::Result {
+        left.fmt_operand(f, precedence)?;
+        write!(f, "{}", operator)?;
+        right.fmt_operand(f, precedence + 1)
+    }
+}
+
+impl FromStr for HExpression {
+    type Err = ParseError;
+
+    fn from_str(formula: &str) -> Result<HExpression, ParseError> {
+        HExpression::parse(formula)
+    }
+}
+
+impl fmt::Display for HExpression {
+    /// Formats the expression as a formula that parses back to the same expression, such as `3*x^2 + sin(y)/2`.
+    /// Only the parentheses needed for precedence are written.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HExpression::Number(value) => write!(f, "{}", value),
+            HExpression::Variable(name) => write!(f, "{}", name),
+            HExpression::Neg(operand) => {
+                write!(f, "-")?;
+                operand.fmt_operand(f, 4)
+            }
+            HExpression::Add(left, right) => HExpression::fmt_binary(f, left, " + ", right, 1),
+            HExpression::Sub(left, right) => HExpression::fmt_binary(f, left, " - ", right, 1),
+            HExpression::Mul(left, right) => HExpression::fmt_binary(f, left, "*", right, 2),
+            HExpression::Div(left, right) => HExpression::fmt_binary(f, left, "/", right, 2),
+            HExpression::Pow(base, exponent) => {
+                // `^` is right associative, so the base needs parentheses at equal precedence and the exponent does not.
+                base.fmt_operand(f, 5)?;
+                write!(f, "^")?;
+                exponent.fmt_operand(f, 4)
+            }
+            HExpression::Function(function, argument) => write!(f, "{}({})", function.name(), argument),
+        }
+    }
+}
+
+
+// Constructors that keep the differentiation rules readable.
+
+fn number(value: f64) -> HExpression {
+    HExpression::Number(value)
+}
+
+fn neg(operand: HExpression) -> HExpression {
+    HExpression::Neg(Box::new(operand))
+}
+
+fn add(left: HExpression, right: HExpression) -> HExpression {
+    HExpression::Add(Box::new(left), Box::new(right))
+}
+
+fn sub(left: HExpression, right: HExpression) -> HExpression {
+    HExpression::Sub(Box::new(left), Box::new(right))
+}
+
+fn mul(left: HExpression, right: HExpression) -> HExpression {
+    HExpression::Mul(Box::new(left), Box::new(right))
+}
+
+fn div(left: HExpression, right: HExpression) -> HExpression {
+    HExpression::Div(Box::new(left), Box::new(right))
+}
+
+fn pow(base: HExpression, exponent: HExpression) -> HExpression {
+    HExpression::Pow(Box::new(base), Box::new(exponent))
+}
+
+fn function(f: HFunction, argument: HExpression) -> HExpression {
+    HExpression::Function(f, Box::new(argument))
+}
+
+
+/// Simplifies a sum (or difference, or negation) whose operands are already simplified, by adding up
+/// the constants and the coefficients of like terms.
+fn simplify_sum(expression: &HExpression) -> HExpression {
+    let mut terms: Vec<(f64, HExpression)> = Vec::new();
+    let mut constant: f64 = 0.0;
+    collect_terms(expression, 1.0, &mut terms, &mut constant);
+
+    let mut result: Option<HExpression> = None;
+    for (coefficient, term) in terms {
+        if coefficient == 0.0 {
+            continue;
+        }
+        let (factors_coefficient, factors) = collect_factors(&term);
+        result = Some(match result {
+            None => build_product(coefficient * factors_coefficient, factors),
+            Some(sum) if coefficient < 0.0 => sub(sum, build_product(-coefficient * factors_coefficient, factors)),
+            Some(sum) => add(sum, build_product(coefficient * factors_coefficient, factors)),
+        });
+    }
+    match result {
+        None => number(constant),
+        Some(sum) if constant < 0.0 => sub(sum, number(-constant)),
+        Some(sum) if constant > 0.0 => add(sum, number(constant)),
+        Some(sum) => sum,
+    }
+}
+
+/// Flattens a sum into `constant + Σ coefficient·term`, where each term is a product with coefficient 1.
+/// Equal terms are merged by adding their coefficients.
+fn collect_terms(expression: &HExpression, sign: f64, terms: &mut Vec<(f64, HExpression)>, constant: &mut f64) {
+    match expression {
+        HExpression::Add(left, right) => {
+            collect_terms(left, sign, terms, constant);
+            collect_terms(right, sign, terms, constant);
+        }
+        HExpression::Sub(left, right) => {
+            collect_terms(left, sign, terms, constant);
+            collect_terms(right, -sign, terms, constant);
+        }
+        HExpression::Neg(operand) => collect_terms(operand, -sign, terms, constant),
+        HExpression::Number(value) => *constant += sign * value,
+        _ => {
+            let (coefficient, factors) = collect_factors(expression);
+            let term: HExpression = build_product(1.0, factors);
+            if let HExpression::Number(value) = term {
+                *constant += sign * coefficient * value;
+                return;
+            }
+            match terms.iter_mut().find(|(_, existing)| *existing == term) {
+                Some((existing_coefficient, _)) => *existing_coefficient += sign * coefficient,
+                None => terms.push((sign * coefficient, term)),
+            }
+        }
+    }
+}
+
+/// Simplifies a product (or quotient, or power) whose operands are already simplified, by multiplying
+/// the constants together and adding the exponents of equal factors.
+fn simplify_product(expression: &HExpression) -> HExpression {
+    let (coefficient, factors) = collect_factors(expression);
+    build_product(coefficient, factors)
+}
+
+/// Flattens a product into `coefficient · Π base^exponent`, merging equal bases by adding their exponents.
+fn collect_factors(expression: &HExpression) -> (f64, Vec<(HExpression, HExpression)>) {
+    fn collect(expression: &HExpression, inverted: bool, coefficient: &mut f64, factors: &mut Vec<(HExpression, HExpression)>) {
+        let (base, exponent): (HExpression, HExpression) = match expression {
+            HExpression::Mul(left, right) => {
+                collect(left, inverted, coefficient, factors);
+                collect(right, inverted, coefficient, factors);
+                return;
+            }
+            HExpression::Div(left, right) => {
+                collect(left, inverted, coefficient, factors);
+                collect(right, !inverted, coefficient, factors);
+                return;
+            }
+            HExpression::Neg(operand) => {
+                *coefficient = -*coefficient;
+                collect(operand, inverted, coefficient, factors);
+                return;
+            }
+            HExpression::Number(value) => {
+                *coefficient *= if inverted { 1.0 / value } else { *value };
+                return;
+            }
+            HExpression::Pow(base, exponent) => ((**base).clone(), (**exponent).clone()),
+            _ => (expression.clone(), number(1.0)),
+        };
+        let exponent: HExpression = if inverted { neg(exponent).simplify_once() } else { exponent };
+        match factors.iter_mut().find(|(existing, _)| *existing == base) {
+            Some((_, existing_exponent)) => {
+                *existing_exponent = add(existing_exponent.clone(), exponent).simplify_once();
+            }
+            None => factors.push((base, exponent)),
+        }
+    }
+
+    let mut coefficient: f64 = 1.0;
+    let mut factors: Vec<(HExpression, HExpression)> = Vec::new();
+    collect(expression, false, &mut coefficient, &mut factors);
+    (coefficient, factors)
+}
+
+/// Builds `coefficient · Π base^exponent` as `c*numerator/denominator`, leaving out unit coefficients and exponents.
+/// Factors with a negative exponent go to the denominator; the factors are sorted by how they are displayed.
+/// A negative sign is put on the leading factor, so the result displays as `-2*x*y` or `-x/y`.
+fn build_product(coefficient: f64, mut factors: Vec<(HExpression, HExpression)>) -> HExpression {
+    if coefficient == 0.0 {
+        return number(0.0);
+    }
+    factors.retain(|(_, exponent)| *exponent != number(0.0));
+    factors.sort_by_cached_key(|(base, _)| base.to_string());
+
+    let mut numerator: Vec<HExpression> = Vec::new();
+    let mut denominator: Vec<HExpression> = Vec::new();
+    for (base, exponent) in factors {
+        match exponent {
+            HExpression::Number(1.0) => numerator.push(base),
+            HExpression::Number(-1.0) => denominator.push(base),
+            HExpression::Number(e) if e < 0.0 => denominator.push(pow(base, number(-e))),
+            HExpression::Neg(e) => denominator.push(pow(base, *e)),
+            exponent => numerator.push(pow(base, exponent)),
+        }
+    }
+
+    let mut numerator = numerator.into_iter();
+    let leading: HExpression = match (coefficient, numerator.next()) {
+        (1.0, Some(first)) => first,
+        (-1.0, Some(first)) => neg(first),
+        (c, Some(first)) => mul(number(c), first),
+        (c, None) => number(c),
+    };
+    let result: HExpression = numerator.fold(leading, mul);
+    match denominator.into_iter().reduce(mul) {
+        Some(denominator) => div(result, denominator),
+        None => result,
+    }
+}
+
+/// Returns the value of a named constant, or None if the name is not a constant.
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" => Some(std::f64::consts::PI),
+        "tau" => Some(std::f64::consts::TAU),
+        "e" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LeftParenthesis,
+    RightParenthesis,
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::Caret => write!(f, "^"),
+            Token::LeftParenthesis => write!(f, "("),
+            Token::RightParenthesis => write!(f, ")"),
+            Token::End => write!(f, "end of input"),
+        }
+    }
+}
+
+
+/// Splits a formula into tokens, each with its character position. The list always ends with `Token::End`.
+fn tokenize(formula: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = formula.chars().collect();
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut i: usize = 0;
+    while i < chars.len() {
+        let start: usize = i;
+        let c: char = chars[i];
+        let token: Token = match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '0'..='9' | '.' => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // An exponent only counts if digits follow, so that `2e` is not swallowed as a malformed number.
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j: usize = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                match text.parse::<f64>() {
+                    Ok(value) => Token::Number(value),
+                    Err(_) => return Err(ParseError::InvalidNumber { text, position: start }),
+                }
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Name(chars[start..i].iter().collect())
+            }
+            _ => {
+                i += 1;
+                match c {
+                    '+' => Token::Plus,
+                    '-' => Token::Minus,
+                    '*' => Token::Star,
+                    '/' => Token::Slash,
+                    '^' => Token::Caret,
+                    '(' => Token::LeftParenthesis,
+                    ')' => Token::RightParenthesis,
+                    _ => return Err(ParseError::UnexpectedCharacter { character: c, position: start }),
+                }
+            }
+        };
+        tokens.push((token, start));
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+
+/// Binding power of a leading `-` or `+`: tighter than `*` and `/`, looser than `^`.
+const PREFIX_BINDING_POWER: u8 = 30;
+
+
+/// The deepest nesting that `HExpression::parse` accepts. Parentheses, signs and function calls each add a level,
+/// and so does every operator, since a chain like `1 + 2 + 3` nests `1 + 2` inside the second `+`. The parser and
+/// every method on the resulting expression recurse once per level, so without a limit a long run of `(` or
+/// of `+ 1` would overflow the stack instead of returning an error.
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+
+/// A Pratt parser over the token list. Each infix operator has a left and a right binding power;
+/// a lower right power than left power makes the operator right associative.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn next(&mut self) -> (Token, usize) {
+        let token: (Token, usize) = self.tokens[self.index].clone();
+        if token.0 != Token::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    /// Checks the height of a new node, one more than that of its highest operand, against `MAX_NESTING_DEPTH`.
+    /// `position` is that of the token that creates the node.
+    fn node_height(operand_height: usize, position: usize) -> Result<usize, ParseError> {
+        if operand_height == MAX_NESTING_DEPTH {
+            return Err(ParseError::TooDeeplyNested { position });
+        }
+        Ok(operand_height + 1)
+    }
+
+    /// Parses an expression and returns it with its height, the number of nodes on its longest branch.
+    /// The height is tracked apart from the recursion depth, since operator chains are parsed in a loop.
+    fn parse_expression(&mut self, min_binding_power: u8) -> Result<(HExpression, usize), ParseError> {
+        if self.depth == MAX_NESTING_DEPTH {
+            return Err(ParseError::TooDeeplyNested { position: self.tokens[self.index].1 });
+        }
+        self.depth += 1;
+        let expression: Result<(HExpression, usize), ParseError> = self.parse_operators(min_binding_power);
+        self.depth -= 1;
+        expression
+    }
+
+    /// Parses an operand followed by every infix operator that binds at least as tightly as `min_binding_power`.
+    fn parse_operators(&mut self, min_binding_power: u8) -> Result<(HExpression, usize), ParseError> {
+        let (mut left, mut height) = self.parse_prefix()?;
+        loop {
+            let (left_power, right_power): (u8, u8) = match self.peek() {
+                Token::Plus | Token::Minus => (10, 11),
+                Token::Star | Token::Slash => (20, 21),
+                Token::Caret => (41, 40),
+                _ => break,
+            };
+            if left_power < min_binding_power {
+                break;
+            }
+            let (operator, position) = self.next();
+            let (right, right_height) = self.parse_expression(right_power)?;
+            height = Self::node_height(height.max(right_height), position)?;
+            let right: Box<HExpression> = Box::new(right);
+            let left_box: Box<HExpression> = Box::new(left);
+            left = match operator {
+                Token::Plus => HExpression::Add(left_box, right),
+                Token::Minus => HExpression::Sub(left_box, right),
+                Token::Star => HExpression::Mul(left_box, right),
+                Token::Slash => HExpression::Div(left_box, right),
+                _ => HExpression::Pow(left_box, right),
+            };
+        }
+        Ok((left, height))
+    }
+
+    fn parse_prefix(&mut self) -> Result<(HExpression, usize), ParseError> {
+        let (token, position) = self.next();
+        match token {
+            Token::Number(value) => Ok((HExpression::Number(value), 1)),
+            Token::Name(name) => {
+                if *self.peek() == Token::LeftParenthesis {
+                    let function: HFunction = HFunction::from_name(&name)
+                        .ok_or(ParseError::UnknownFunction { name, position })?;
+                    let (argument, height) = self.parse_parenthesized()?;
+                    let height: usize = Self::node_height(height, position)?;
+                    return Ok((HExpression::Function(function, Box::new(argument)), height));
+                }
+                let expression: HExpression = match constant(&name) {
+                    Some(value) => HExpression::Number(value),
+                    None => HExpression::Variable(name),
+                };
+                Ok((expression, 1))
+            }
+            Token::Minus => {
+                let (operand, height) = self.parse_expression(PREFIX_BINDING_POWER)?;
+                Ok((HExpression::Neg(Box::new(operand)), Self::node_height(height, position)?))
+            }
+            Token::Plus => self.parse_expression(PREFIX_BINDING_POWER),
+            Token::LeftParenthesis => {
+                self.index -= 1;
+                self.parse_parenthesized()
+            }
+            Token::End => Err(ParseError::UnexpectedEnd { position }),
+            _ => Err(ParseError::UnexpectedToken { token: token.to_string(), position }),
+        }
+    }
+
+    /// Parses `( expression )`, starting at the `(`.
+    fn parse_parenthesized(&mut self) -> Result<(HExpression, usize), ParseError> {
+        let (_, open_position) = self.next();
+        let inner: (HExpression, usize) = self.parse_expression(0)?;
+        match self.next() {
+            (Token::RightParenthesis, _) => Ok(inner),
+            (Token::End, _) => Err(ParseError::UnclosedParenthesis { position: open_position }),
+            (token, position) => Err(ParseError::UnexpectedToken { token: token.to_string(), position }),
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(formula: &str, variables: &[(&str, f64)]) -> f64 {
+        let variables: HashMap<&str, f64> = variables.iter().copied().collect();
+        HExpression::parse(formula).unwrap().h_evaluate(&variables).unwrap()
+    }
+
+    #[test]
+    fn test_expression_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3", &[]), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3", &[]), 9.0);
+        assert_eq!(evaluate("10 - 4 - 3", &[]), 3.0);
+        assert_eq!(evaluate("24 / 4 / 2", &[]), 3.0);
+        assert_eq!(evaluate("2^3^2", &[]), 512.0);
+        assert_eq!(evaluate("-2^2", &[]), -4.0);
+        assert_eq!(evaluate("2^-1", &[]), 0.5);
+        assert_eq!(evaluate("-3 * -2", &[]), 6.0);
+        assert_eq!(evaluate("+4 - +1", &[]), 3.0);
+        assert_eq!(evaluate("1.5e2 + .5", &[]), 150.5);
+    }
+
+    #[test]
+    fn test_expression_functions_and_variables() {
+        assert_eq!(evaluate("3*x^2 + sin(y)/2", &[("x", 2.0), ("y", 0.0)]), 12.0);
+        assert!((evaluate("cos(pi) + ln(e) + sqrt(16)", &[]) - 4.0).abs() < 1e-12);
+        assert_eq!(evaluate("abs(rate_1 - 10) + log(100)", &[("rate_1", 4.0)]), 8.0);
+
+        let expression = HExpression::parse("a * b + a").unwrap();
+        assert_eq!(expression.variables(), vec!["a".to_string(), "b".to_string()]);
+        let variables: HashMap<String, f64> = HashMap::from([("a".to_string(), 1.0)]);
+        assert_eq!(
+            expression.h_evaluate(&variables),
+            Err(EvaluationError::UnknownVariable { name: "b".to_string() })
+        );
+        assert_eq!("x + 1".parse::<HExpression>(), HExpression::parse("x + 1"));
+    }
+
+    #[test]
+    fn test_expression_parse_errors() {
+        assert_eq!(HExpression::parse("2 + "), Err(ParseError::UnexpectedEnd { position: 4 }));
+        assert_eq!(HExpression::parse(""), Err(ParseError::UnexpectedEnd { position: 0 }));
+        assert_eq!(
+            HExpression::parse("2 * * 3"),
+            Err(ParseError::UnexpectedToken { token: "*".to_string(), position: 4 })
+        );
+        assert_eq!(
+            HExpression::parse("2 # 3"),
+            Err(ParseError::UnexpectedCharacter { character: '#', position: 2 })
+        );
+        assert_eq!(
+            HExpression::parse("1.2.3"),
+            Err(ParseError::InvalidNumber { text: "1.2.3".to_string(), position: 0 })
+        );
+        assert_eq!(
+            HExpression::parse("1 + foo(2)"),
+            Err(ParseError::UnknownFunction { name: "foo".to_string(), position: 4 })
+        );
+        assert_eq!(HExpression::parse("sin(x + (1)"), Err(ParseError::UnclosedParenthesis { position: 3 }));
+        assert_eq!(
+            HExpression::parse("(x))"),
+            Err(ParseError::UnexpectedToken { token: ")".to_string(), position: 3 })
+        );
+        assert_eq!(HExpression::parse("2 x").unwrap_err().position(), 2);
+    }
+
+    #[test]
+    fn test_expression_nesting_limit() {
+        // The whole formula is one level, and each `(` or leading `-` adds one more.
+        let depth: usize = MAX_NESTING_DEPTH - 1;
+        let nested: String = format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(evaluate(&nested, &[("x", 2.0)]), 2.0);
+
+        let too_deep: String = format!("{}x{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert_eq!(
+            HExpression::parse(&too_deep),
+            Err(ParseError::TooDeeplyNested { position: MAX_NESTING_DEPTH })
+        );
+        let signs: String = format!("{}1", "-".repeat(10_000));
+        assert_eq!(HExpression::parse(&signs).unwrap_err().position(), MAX_NESTING_DEPTH);
+        let powers: String = vec!["2"; 10_000].join("^");
+        assert!(matches!(HExpression::parse(&powers), Err(ParseError::TooDeeplyNested { .. })));
+
+        // Each operator of a left associative chain nests the chain before it one level deeper.
+        let chain: String = format!("1{}", "+1".repeat(MAX_NESTING_DEPTH - 1));
+        assert_eq!(evaluate(&chain, &[]), MAX_NESTING_DEPTH as f64);
+        let long_chain: String = format!("1{}", "+1".repeat(200_000));
+        assert_eq!(
+            HExpression::parse(&long_chain),
+            Err(ParseError::TooDeeplyNested { position: 2 * MAX_NESTING_DEPTH - 1 })
+        );
+        // Chains inside parentheses add up too.
+        let mut nested_chains: String = "1".to_string();
+        for _ in 0..100 {
+            nested_chains = format!("({}){}", nested_chains, "+1".repeat(100));
+        }
+        assert!(matches!(HExpression::parse(&nested_chains), Err(ParseError::TooDeeplyNested { .. })));
+    }
+
+    #[test]
+    fn test_expression_display() {
+        for formula in ["3*x^2 + sin(y)/2", "a - (b - c)", "(a + b)*c", "-x^2", "(-x)^2", "2^3^2", "(2^3)^2", "a/(b*c)", "-(a + b)"] {
+            let expression = HExpression::parse(formula).unwrap();
+            assert_eq!(expression.to_string(), formula);
+            assert_eq!(HExpression::parse(&expression.to_string()).unwrap(), expression);
+        }
+        assert_eq!(HExpression::parse("((x))*(2)").unwrap().to_string(), "x*2");
+    }
+
+    #[test]
+    fn test_expression_simplify() {
+        let simplify = |formula: &str| HExpression::parse(formula).unwrap().h_simplify().to_string();
+        assert_eq!(simplify("2 + 3*4"), "14");
+        assert_eq!(simplify("sqrt(16) + x*0"), "4");
+        assert_eq!(simplify("x + 0"), "x");
+        assert_eq!(simplify("1*x^1/1"), "x");
+        assert_eq!(simplify("x^0"), "1");
+        assert_eq!(simplify("-(-x)"), "x");
+        assert_eq!(simplify("x + 2*x - y"), "3*x - y");
+        assert_eq!(simplify("x - x"), "0");
+        assert_eq!(simplify("x - 2*x"), "-x");
+        assert_eq!(simplify("x*y/x"), "y");
+        assert_eq!(simplify("x*x^2/x"), "x^2");
+        assert_eq!(simplify("y*x + x*y"), "2*x*y");
+        assert_eq!(simplify("-(2*x*y)"), "-2*x*y");
+        assert_eq!(simplify("2*x*3 + 0*y + x^1 - 4/2"), "7*x - 2");
+        assert_eq!(simplify("(x + 1)*(x + 1)"), "(x + 1)^2");
+        assert_eq!(simplify("3*x^2 - 2*x + 1"), "3*x^2 - 2*x + 1");
+    }
+
+    #[test]
+    fn test_expression_derivative() {
+        let derivative = |formula: &str, variable: &str| HExpression::parse(formula).unwrap().h_derivative(variable).to_string();
+        assert_eq!(derivative("3*x^2 + sin(y)/2", "x"), "6*x");
+        assert_eq!(derivative("3*x^2 + sin(y)/2", "y"), "0.5*cos(y)");
+        assert_eq!(derivative("7", "x"), "0");
+        assert_eq!(derivative("sin(x^2)", "x"), "2*cos(x^2)*x");
+        assert_eq!(derivative("x*exp(x)", "x"), "exp(x) + exp(x)*x");
+        assert_eq!(derivative("1/x", "x"), "-1/x^2");
+        assert_eq!(derivative("x/(1 + x)", "x"), "1/(x + 1)^2");
+        assert_eq!(derivative("x^x", "x"), "(ln(x) + 1)*x^x");
+        assert_eq!(derivative("cos(x)", "x"), "-sin(x)");
+    }
+
+    #[test]
+    fn test_expression_derivative_matches_finite_differences() {
+        let formulas = [
+            "tan(x)", "asin(x)", "acos(x)", "atan(x^2)", "sinh(x)*cosh(x)", "tanh(x)", "ln(x)/x",
+            "log(x)", "sqrt(x^2 + 1)", "abs(x - 1)", "2^x", "x^y", "exp(-x^2)/(1 + y*x)",
+        ];
+        let (x, y, h): (f64, f64, f64) = (0.3, 1.7, 1e-6);
+        for formula in formulas {
+            let expression = HExpression::parse(formula).unwrap();
+            let at = |x: f64| expression.h_evaluate(&HashMap::from([("x", x), ("y", y)])).unwrap();
+            let numeric: f64 = (at(x + h) - at(x - h)) / (2.0 * h);
+            let symbolic: f64 = expression.h_derivative("x").h_evaluate(&HashMap::from([("x", x), ("y", y)])).unwrap();
+            assert!((numeric - symbolic).abs() < 1e-6, "{}: {} != {}", formula, symbolic, numeric);
+        }
+
+        let gradient = HExpression::parse("x^2*y").unwrap().h_gradient(&["x", "y"]);
+        assert_eq!(gradient[0].to_string(), "2*x*y");
+        assert_eq!(gradient[1].to_string(), "x^2");
+    }
+}
@@ -0,0 +1,515 @@
+use crate::prelude::*;
+use crate::linear_algebra::elimination_tolerance;
+use std::fmt;
+use std::ops::{Add, Deref, DerefMut, Index, IndexMut, Mul, Neg, Sub};
+
+
+/// A stack-allocated matrix with `R` rows and `C` columns, for small fixed sizes such as the 2x2, 3x3 and 4x4
+/// matrices used in graphics and physics. The dimensions are part of the type, so multiplying a 2x3 matrix by a
+/// 2x3 matrix does not compile, where `HMatrix` would return `None` or panic at runtime.
+/// Convert to and from `HMatrix` with `to_h_matrix` and `from_h_matrix` to use the operations that only exist there.
+///
+/// Example:
+///
+/// let rotation: HMatrixN<2, 2> = HMatrixN::new([[0, -1], [1, 0]]);
+/// let point = HVectorN::new([1.0, 0.0]);
+/// assert_eq!(rotation * point, HVectorN::new([0.0, 1.0]));
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HMatrixN<const R: usize, const C: usize> {
+    data: [[f64; C]; R],
+}
+
+/// A stack-allocated vector with `N` elements, the fixed-size counterpart of a `Vec<f64>` used as a vector.
+/// It dereferences to `[f64]`, so the slice functions and traits such as `h_dot` and `h_magnitude` work on it too.
+///
+/// Example:
+///
+/// let a = HVectorN::new([1.0, 2.0, 2.0]);
+/// assert_eq!(a.h_magnitude(), 3.0);
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HVectorN<const N: usize> {
+    data: [f64; N],
+}
+
+
+impl<const R: usize, const C: usize> HMatrixN<R, C> {
+    /// Creates a matrix from an array of rows.
+    pub fn new<T>(rows: [[T; C]; R]) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        HMatrixN {
+            data: rows.map(|row| row.map(|x| x.into())),
+        }
+    }
+
+    /// Creates a matrix where every element is zero.
+    pub fn zeros() -> Self {
+        HMatrixN { data: [[0.0; C]; R] }
+    }
+
+    /// Creates a matrix by calling `f(row, col)` for every element.
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> f64,
+    {
+        HMatrixN {
+            data: std::array::from_fn(|r| std::array::from_fn(|c| f(r, c))),
+        }
+    }
+
+    /// Creates a matrix from an `HMatrix`. If the `HMatrix` is not `R x C`, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix: HMatrixN<2, 2> = HMatrixN::from_h_matrix(&h_matrix![1, 2; 3, 4]).unwrap();
+    /// assert!(HMatrixN::<3, 3>::from_h_matrix(&h_matrix![1, 2; 3, 4]).is_none());
+    pub fn from_h_matrix<T>(matrix: &HMatrix<T>) -> Option<Self>
+    where
+        T: Copy + Into<f64>,
+    {
+        if matrix.shape() != (R, C) {
+            return None;
+        }
+        Some(Self::from_fn(|r, c| matrix[(r, c)].into()))
+    }
+
+    /// Converts the matrix into an `HMatrix`.
+    pub fn to_h_matrix(&self) -> HMatrix<f64> {
+        HMatrix::from_flat(R, C, self.data.as_flattened().to_vec()).unwrap()
+    }
+
+    /// Returns the dimensions of the matrix as `(rows, columns)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (R, C)
+    }
+
+    /// Retrieves the value at the specified row and column indices.
+    /// If the indices are out of bounds, the function returns `None`.
+    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
+        self.data.get(row)?.get(col).copied()
+    }
+
+    /// Returns the row at the specified index as a vector.
+    /// If the index is out of bounds, the function returns `None`.
+    pub fn get_row(&self, index: usize) -> Option<HVectorN<C>> {
+        self.data.get(index).map(|row| HVectorN { data: *row })
+    }
+
+    /// Returns the column at the specified index as a vector.
+    /// If the index is out of bounds, the function returns `None`.
+    pub fn get_col(&self, index: usize) -> Option<HVectorN<R>> {
+        if index >= C {
+            return None;
+        }
+        Some(HVectorN {
+            data: self.data.map(|row| row[index]),
+        })
+    }
+
+    /// Returns the transpose of the matrix, which swaps the rows and columns.
+    pub fn h_transpose(&self) -> HMatrixN<C, R> {
+        HMatrixN::from_fn(|r, c| self.data[c][r])
+    }
+
+    /// Returns `true` if every element is within `tolerance` of the corresponding element of `other`.
+    pub fn h_approx_eq(&self, other: &HMatrixN<R, C>, tolerance: f64) -> bool {
+        self.data
+            .as_flattened()
+            .iter()
+            .zip(other.data.as_flattened())
+            .all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+impl<const N: usize> HMatrixN<N, N> {
+    /// Creates an identity matrix, with ones on the diagonal and zeros elsewhere.
+    pub fn identity() -> Self {
+        Self::from_fn(|r, c| if r == c { 1.0 } else { 0.0 })
+    }
+
+    /// Creates a matrix with `diagonal` on the diagonal and zeros elsewhere.
+    pub fn from_diagonal<T>(diagonal: [T; N]) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        Self::from_fn(|r, c| if r == c { diagonal[r].into() } else { 0.0 })
+    }
+
+    /// Calculates the determinant with Gaussian elimination and partial pivoting, without allocating.
+    ///
+    /// Example:
+    ///
+    /// let matrix: HMatrixN<2, 2> = HMatrixN::new([[2.0, 1.0], [4.0, 3.0]]);
+    /// assert_eq!(matrix.h_determinant(), 2.0);
+    pub fn h_determinant(&self) -> f64 {
+        let mut a: [[f64; N]; N] = self.data;
+        let tolerance: f64 = elimination_tolerance(a.as_flattened(), N, N);
+        let mut determinant: f64 = 1.0;
+        for k in 0..N {
+            let pivot_row: usize = (k..N).fold(k, |best, r| if a[r][k].abs() > a[best][k].abs() { r } else { best });
+            if a[pivot_row][k].abs() <= tolerance {
+                return 0.0;
+            }
+            if pivot_row != k {
+                a.swap(pivot_row, k);
+                determinant = -determinant;
+            }
+            determinant *= a[k][k];
+            let pivot_values: [f64; N] = a[k];
+            for row in a.iter_mut().skip(k + 1) {
+                let factor: f64 = row[k] / pivot_values[k];
+                for (value, pivot_value) in row.iter_mut().zip(pivot_values).skip(k) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+        determinant
+    }
+
+    /// Calculates the inverse with Gauss-Jordan elimination and partial pivoting, without allocating.
+    /// If the matrix is singular (a pivot is numerically zero), the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix: HMatrixN<2, 2> = HMatrixN::new([[4.0, 7.0], [2.0, 6.0]]);
+    /// let inverse = matrix.h_inverse().unwrap();
+    /// inverse is approximately [[0.6, -0.7], [-0.2, 0.4]]
+    pub fn h_inverse(&self) -> Option<Self> {
+        let mut a: [[f64; N]; N] = self.data;
+        let mut inverse: [[f64; N]; N] = Self::identity().data;
+        let tolerance: f64 = elimination_tolerance(a.as_flattened(), N, N);
+        for k in 0..N {
+            let pivot_row: usize = (k..N).fold(k, |best, r| if a[r][k].abs() > a[best][k].abs() { r } else { best });
+            if a[pivot_row][k].abs() <= tolerance {
+                return None;
+            }
+            a.swap(pivot_row, k);
+            inverse.swap(pivot_row, k);
+            let pivot: f64 = a[k][k];
+            for c in 0..N {
+                a[k][c] /= pivot;
+                inverse[k][c] /= pivot;
+            }
+            for r in 0..N {
+                if r == k {
+                    continue;
+                }
+                let factor: f64 = a[r][k];
+                for c in 0..N {
+                    a[r][c] -= factor * a[k][c];
+                    inverse[r][c] -= factor * inverse[k][c];
+                }
+            }
+        }
+        Some(HMatrixN { data: inverse })
+    }
+}
+
+impl<const R: usize, const C: usize> Default for HMatrixN<R, C> {
+    fn default() -> Self {
+        Self::zeros()
+    }
+}
+
+impl<const R: usize, const C: usize> From<HMatrixN<R, C>> for HMatrix<f64> {
+    fn from(matrix: HMatrixN<R, C>) -> Self {
+        matrix.to_h_matrix()
+    }
+}
+
+impl<const R: usize, const C: usize> fmt::Display for HMatrixN<R, C> {
+    /// Formats the matrix the same way as `HMatrix`, with one row per line and aligned columns.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_h_matrix(), f)
+    }
+}
+
+impl<const R: usize, const C: usize> Index<(usize, usize)> for HMatrixN<R, C> {
+    type Output = f64;
+
+    /// Returns the element at `(row, col)`. Panics if the indices are out of bounds.
+    fn index(&self, (row, col): (usize, usize)) -> &f64 {
+        &self.data[row][col]
+    }
+}
+
+impl<const R: usize, const C: usize> IndexMut<(usize, usize)> for HMatrixN<R, C> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut f64 {
+        &mut self.data[row][col]
+    }
+}
+
+impl<const R: usize, const C: usize> Add for HMatrixN<R, C> {
+    type Output = HMatrixN<R, C>;
+
+    fn add(self, other: HMatrixN<R, C>) -> HMatrixN<R, C> {
+        HMatrixN::from_fn(|r, c| self.data[r][c] + other.data[r][c])
+    }
+}
+
+impl<const R: usize, const C: usize> Sub for HMatrixN<R, C> {
+    type Output = HMatrixN<R, C>;
+
+    fn sub(self, other: HMatrixN<R, C>) -> HMatrixN<R, C> {
+        HMatrixN::from_fn(|r, c| self.data[r][c] - other.data[r][c])
+    }
+}
+
+impl<const R: usize, const C: usize> Neg for HMatrixN<R, C> {
+    type Output = HMatrixN<R, C>;
+
+    fn neg(self) -> HMatrixN<R, C> {
+        HMatrixN::from_fn(|r, c| -self.data[r][c])
+    }
+}
+
+impl<const R: usize, const C: usize, const K: usize> Mul<HMatrixN<C, K>> for HMatrixN<R, C> {
+    type Output = HMatrixN<R, K>;
+
+    /// Multiplies two matrices. The inner dimensions are checked at compile time,
+    /// so this is the fixed-size equivalent of `h_linear_composition(other, self)`.
+    fn mul(self, other: HMatrixN<C, K>) -> HMatrixN<R, K> {
+        HMatrixN::from_fn(|r, k| (0..C).map(|c| self.data[r][c] * other.data[c][k]).sum())
+    }
+}
+
+impl<const R: usize, const C: usize> Mul<HVectorN<C>> for HMatrixN<R, C> {
+    type Output = HVectorN<R>;
+
+    /// Multiplies the matrix by a column vector, the same as `vector.h_linear_transform(&matrix)`.
+    fn mul(self, vector: HVectorN<C>) -> HVectorN<R> {
+        vector.h_linear_transform(&self)
+    }
+}
+
+impl<const R: usize, const C: usize> Mul<f64> for HMatrixN<R, C> {
+    type Output = HMatrixN<R, C>;
+
+    fn mul(self, scalar: f64) -> HMatrixN<R, C> {
+        HMatrixN::from_fn(|r, c| self.data[r][c] * scalar)
+    }
+}
+
+impl<const R: usize, const C: usize> Mul<HMatrixN<R, C>> for f64 {
+    type Output = HMatrixN<R, C>;
+
+    fn mul(self, matrix: HMatrixN<R, C>) -> HMatrixN<R, C> {
+        matrix * self
+    }
+}
+
+
+impl<const N: usize> HVectorN<N> {
+    /// Creates a vector from an array of elements.
+    pub fn new<T>(elements: [T; N]) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        HVectorN {
+            data: elements.map(|x| x.into()),
+        }
+    }
+
+    /// Creates a vector where every element is zero.
+    pub fn zeros() -> Self {
+        HVectorN { data: [0.0; N] }
+    }
+
+    /// Creates a vector from a slice. If the slice does not have `N` elements, the function returns `None`.
+    pub fn from_slice<T>(elements: &[T]) -> Option<Self>
+    where
+        T: Copy + Into<f64>,
+    {
+        if elements.len() != N {
+            return None;
+        }
+        Some(HVectorN {
+            data: std::array::from_fn(|i| elements[i].into()),
+        })
+    }
+
+    /// Returns the elements as an array.
+    pub fn to_array(&self) -> [f64; N] {
+        self.data
+    }
+
+    /// Calculates the dot product with another vector of the same length.
+    pub fn h_dot(&self, other: &HVectorN<N>) -> f64 {
+        h_dot(&self.data, &other.data)
+    }
+
+    /// Returns the vector scaled to a magnitude of 1. Returns `VectorError::ZeroVector` if the vector is zero.
+    pub fn h_normalize(&self) -> Result<HVectorN<N>, VectorError> {
+        let magnitude: f64 = self.h_magnitude();
+        if magnitude == 0.0 {
+            return Err(VectorError::ZeroVector);
+        }
+        Ok(*self * (1.0 / magnitude))
+    }
+
+    /// Applies a linear transformation to the vector, by multiplying it by `matrix` from the left.
+    /// Unlike `LinearTransform::h_linear_transform`, the matrix must have `N` columns at compile time,
+    /// so the result is always defined.
+    ///
+    /// Example:
+    ///
+    /// let matrix: HMatrixN<2, 2> = HMatrixN::new([[1, 2], [3, 4]]);
+    /// let vector = HVectorN::new([1, 2]);
+    /// assert_eq!(vector.h_linear_transform(&matrix), HVectorN::new([5.0, 11.0]));
+    pub fn h_linear_transform<const R: usize>(&self, matrix: &HMatrixN<R, N>) -> HVectorN<R> {
+        HVectorN {
+            data: std::array::from_fn(|r| h_dot(&matrix.data[r], &self.data)),
+        }
+    }
+}
+
+impl HVectorN<3> {
+    /// Calculates the cross product with another 3-dimensional vector (see `h_cross`).
+    pub fn h_cross(&self, other: &HVectorN<3>) -> HVectorN<3> {
+        let [a0, a1, a2] = self.data;
+        let [b0, b1, b2] = other.data;
+        HVectorN {
+            data: [a1 * b2 - a2 * b1, a2 * b0 - a0 * b2, a0 * b1 - a1 * b0],
+        }
+    }
+}
+
+impl<const N: usize> Default for HVectorN<N> {
+    fn default() -> Self {
+        Self::zeros()
+    }
+}
+
+impl<const N: usize> Deref for HVectorN<N> {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.data
+    }
+}
+
+impl<const N: usize> DerefMut for HVectorN<N> {
+    fn deref_mut(&mut self) -> &mut [f64] {
+        &mut self.data
+    }
+}
+
+impl<const N: usize> From<[f64; N]> for HVectorN<N> {
+    fn from(data: [f64; N]) -> Self {
+        HVectorN { data }
+    }
+}
+
+impl<const N: usize> From<HVectorN<N>> for Vec<f64> {
+    fn from(vector: HVectorN<N>) -> Self {
+        vector.data.to_vec()
+    }
+}
+
+impl<const N: usize> Add for HVectorN<N> {
+    type Output = HVectorN<N>;
+
+    fn add(self, other: HVectorN<N>) -> HVectorN<N> {
+        HVectorN {
+            data: std::array::from_fn(|i| self.data[i] + other.data[i]),
+        }
+    }
+}
+
+impl<const N: usize> Sub for HVectorN<N> {
+    type Output = HVectorN<N>;
+
+    fn sub(self, other: HVectorN<N>) -> HVectorN<N> {
+        HVectorN {
+            data: std::array::from_fn(|i| self.data[i] - other.data[i]),
+        }
+    }
+}
+
+impl<const N: usize> Neg for HVectorN<N> {
+    type Output = HVectorN<N>;
+
+    fn neg(self) -> HVectorN<N> {
+        HVectorN { data: self.data.map(|x| -x) }
+    }
+}
+
+impl<const N: usize> Mul<f64> for HVectorN<N> {
+    type Output = HVectorN<N>;
+
+    fn mul(self, scalar: f64) -> HVectorN<N> {
+        HVectorN { data: self.data.map(|x| x * scalar) }
+    }
+}
+
+impl<const N: usize> Mul<HVectorN<N>> for f64 {
+    type Output = HVectorN<N>;
+
+    fn mul(self, vector: HVectorN<N>) -> HVectorN<N> {
+        vector * self
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::h_matrix;
+
+    #[test]
+    fn test_fixed_matrix_conversion() {
+        let matrix: HMatrixN<2, 3> = HMatrixN::new([[1, 2, 3], [4, 5, 6]]);
+        let dynamic = matrix.to_h_matrix();
+        assert_eq!(dynamic, h_matrix![1.0, 2.0, 3.0; 4.0, 5.0, 6.0]);
+        assert_eq!(HMatrixN::<2, 3>::from_h_matrix(&dynamic), Some(matrix));
+        assert!(HMatrixN::<3, 2>::from_h_matrix(&dynamic).is_none());
+        assert_eq!(HMatrix::from(matrix.h_transpose()), dynamic.h_transpose());
+    }
+
+    #[test]
+    fn test_fixed_matrix_operators() {
+        let a: HMatrixN<2, 3> = HMatrixN::new([[1, 2, 3], [4, 5, 6]]);
+        let b: HMatrixN<3, 2> = HMatrixN::new([[7, 8], [9, 10], [11, 12]]);
+        assert_eq!(a * b, HMatrixN::new([[58, 64], [139, 154]]));
+        assert_eq!((a * b).to_h_matrix(), &a.to_h_matrix() * &b.to_h_matrix());
+        assert_eq!(a + a, 2.0 * a);
+        assert_eq!(a - a, HMatrixN::zeros());
+        assert_eq!(-a, a * -1.0);
+        assert_eq!(a * HVectorN::new([1, 0, -1]), HVectorN::new([-2.0, -2.0]));
+        assert_eq!(a[(1, 2)], 6.0);
+        assert_eq!(a.get(2, 0), None);
+        assert_eq!(a.get_col(1), Some(HVectorN::new([2.0, 5.0])));
+    }
+
+    #[test]
+    fn test_fixed_matrix_determinant_and_inverse() {
+        let matrix: HMatrixN<3, 3> = HMatrixN::new([[2.0, 0.0, 1.0], [1.0, 3.0, 2.0], [1.0, 1.0, 2.0]]);
+        let expected: f64 = matrix.to_h_matrix().h_determinant().unwrap();
+        assert!((matrix.h_determinant() - expected).abs() < 1e-12);
+
+        let inverse = matrix.h_inverse().unwrap();
+        assert!((matrix * inverse).h_approx_eq(&HMatrixN::identity(), 1e-12));
+
+        let singular: HMatrixN<2, 2> = HMatrixN::new([[1, 2], [2, 4]]);
+        assert_eq!(singular.h_determinant(), 0.0);
+        assert!(singular.h_inverse().is_none());
+    }
+
+    #[test]
+    fn test_fixed_vector() {
+        let a = HVectorN::new([1.0, 2.0, 2.0]);
+        let b = HVectorN::new([3, 0, -1]);
+        assert_eq!(a.h_magnitude(), 3.0);
+        assert_eq!(a.h_dot(&b), 1.0);
+        assert_eq!(a + b, HVectorN::new([4.0, 2.0, 1.0]));
+        assert_eq!(a - b, HVectorN::new([-2.0, 2.0, 3.0]));
+        assert!((a.h_normalize().unwrap().h_magnitude() - 1.0).abs() < 1e-12);
+        assert_eq!(HVectorN::<3>::zeros().h_normalize(), Err(VectorError::ZeroVector));
+        assert_eq!(HVectorN::<2>::from_slice(&[1, 2]), Some(HVectorN::new([1.0, 2.0])));
+        assert!(HVectorN::<2>::from_slice(&[1, 2, 3]).is_none());
+        assert_eq!(Vec::from(a), vec![1.0, 2.0, 2.0]);
+        assert_eq!(Vec::from(a.h_cross(&b)), h_cross(&a, &b).unwrap());
+    }
+}
@@ -7,15 +7,20 @@
 pub mod prelude;
 
 pub mod algebra;
+pub mod complex;
 pub mod conversions;
 pub mod core_math;
+pub mod expression;
 pub mod finance;
+pub mod fixed_matrix;
 pub mod functionality;
 pub mod geometry;
 pub mod linear_algebra;
 pub mod probability;
+pub mod sparse_matrix;
 pub mod statistics;
 pub mod terminal_input;
+pub mod transforms;
 pub mod machine_learning;
 
 
@@ -1,21 +1,47 @@
+use crate::complex::HComplex;
+use std::fmt;
 use std::iter::zip;
+use std::ops::{Add, Deref, DerefMut, Div, Index, IndexMut, Mul, Neg, Sub};
+
+
+/// Describes why a linear system `Ax = b` could not be solved, by `HMatrix::h_solve` and the other solvers.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LinearSystemError {
+    /// The right-hand side does not have one entry per equation (row) of the matrix.
+    DimensionMismatch { equations: usize, rhs_len: usize },
+    /// The method only works for square matrices, but the matrix has a different number of rows and columns.
+    NotSquare { rows: usize, cols: usize },
+    /// The matrix is square but singular, so the system has either no solution or infinitely many.
+    Singular,
+    /// There are fewer independent equations than unknowns, so the solution is not unique.
+    Underdetermined { rank: usize, unknowns: usize },
+    /// There are more equations than unknowns and they contradict each other, so no exact solution exists.
+    Overdetermined { equations: usize, unknowns: usize },
+    /// An iterative solver that requires a symmetric positive-definite matrix found that the matrix is not.
+    NotPositiveDefinite,
+    /// An iterative solver did not reach the requested tolerance within the allowed number of iterations.
+    NotConverged { iterations: usize },
+}
 
 
 /// A struct representing a matrix, which is a 2D array of values.
 /// The matrix is stored in a flat vector (`data`) along with its dimensions 
 /// (`row_size` and `columm_size`).
+#[derive(Debug, Clone, PartialEq)]
 pub struct HMatrix<T>
 where
-    T: Copy + Into<f64>,
+    T: Copy,
 {
     data: Vec<T>,
     row_size: usize,
     columm_size: usize,
 }
 
+// Construction, access and reshaping only move elements around, so they work for any element type,
+// including `HComplex`. The numeric routines in the next impl block convert the elements to `f64`.
 impl<T> HMatrix<T>
 where
-    T: Copy + Into<f64>,
+    T: Copy,
 {
     pub fn new() -> Self {
         HMatrix { 
@@ -64,7 +90,7 @@ where
             return None;
         }
         let mut data: Vec<T> = Vec::new();
-        let row_size: usize = cols[0].len();
+        let columm_size: usize = cols[0].len();
         let mut columm_size_prev: Option<usize> = None;
 
         for r_i in 0..cols[0].len() {
@@ -83,11 +109,53 @@ where
         }
         Some(HMatrix {
             data,
-            row_size,
-            columm_size: cols.len(),
+            row_size: cols.len(),
+            columm_size,
+        })
+    }
+
+    /// Creates a new `HMatrix` with `rows` rows and `cols` columns from a flat vector of values in row-major order,
+    /// so the first `cols` values form the first row. If `data.len()` is not `rows * cols`, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::from_flat(2, 3, vec![1, 2, 3, 4, 5, 6]).unwrap();
+    /// matrix is the 2x3 matrix: [[1, 2, 3], [4, 5, 6]]
+    pub fn from_flat(rows: usize, cols: usize, data: Vec<T>) -> Option<Self> {
+        if data.len() != rows * cols {
+            return None;
+        }
+        Some(HMatrix {
+            data,
+            row_size: cols,
+            columm_size: rows,
         })
     }
 
+    /// Creates a new `HMatrix` with `rows` rows and `cols` columns, where the element at row `i` and column `j`
+    /// is `f(i, j)`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::from_fn(2, 2, |i, j| (i * 2 + j) as f64);
+    /// matrix is the 2x2 matrix: [[0.0, 1.0], [2.0, 3.0]]
+    pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let mut data: Vec<T> = Vec::with_capacity(rows * cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                data.push(f(i, j));
+            }
+        }
+        HMatrix {
+            data,
+            row_size: cols,
+            columm_size: rows,
+        }
+    }
+
     /// Retrieves the value at the specified row and column indices. If the indices are out of bounds,
     /// the function returns `None`. Otherwise, it returns the value wrapped in `Some`.
     pub fn get(&self, row: usize, col: usize) -> Option<T> {
@@ -181,6 +249,12 @@ where
         }
     }
     pub fn add_col(&mut self, col: Vec<T>) -> Result<(), String> {
+        if self.columm_size == 0 && self.row_size == 0 {
+            self.columm_size = col.len();
+            self.data = col;
+            self.row_size = 1;
+            return Ok(());
+        }
         if col.len() != self.columm_size {
             return Err(format!(
                 "column length {} does not match matrix row count {}",
@@ -195,193 +269,3054 @@ where
         self.row_size += 1;
         Ok(())
     }
-}
 
+    /// Inserts a row at the given index, moving the rows at and after it down by one.
+    /// An index equal to the number of rows appends the row at the bottom, like `add_row`.
+    /// Returns an error if the index is out of bounds, the row is empty, or the row does not have the same length
+    /// as the other rows.
+    pub fn insert_row(&mut self, index: usize, row: Vec<T>) -> Result<(), String> {
+        if row.is_empty() {
+            return Err(String::from("cannot insert an empty row. from: HMatrix, insert_row()"));
+        }
+        if index > self.columm_size {
+            return Err(format!(
+                "row index {} is out of bounds for a matrix with {} rows. from: HMatrix, insert_row()",
+                index, self.columm_size
+            ));
+        }
+        if self.columm_size == 0 && self.row_size == 0 {
+            return self.add_row(row);
+        }
+        if row.len() != self.row_size {
+            return Err(format!(
+                "row length {} does not match matrix column count {}. from: HMatrix, insert_row()",
+                row.len(),
+                self.row_size
+            ));
+        }
+        let start: usize = index * self.row_size;
+        self.data.splice(start..start, row);
+        self.columm_size += 1;
+        Ok(())
+    }
 
-/// Hadamard product (element-wise multiplication) of two vectors.
-/// Both vectors must have the same length.
-/// Formula: C = A ⊙ B, where C[i] = A[i] * B[i]
-///
-/// Example:
-/// 
-/// let vec1 = vec![1.0, 2.0, 3.0];
-/// let vec2 = vec![4.0, 5.0, 6.0];
-/// let result = h_hadamard(&vec1, &vec2);
-/// assert_eq!(result, vec![4.0, 10.0, 18.0]);
-/// The Hadamard product is calculated as (1*4, 2*5, 3*6) = (4.0, 10.0, 18.0).
+    /// Removes the row at the given index and returns it, moving the rows after it up by one.
+    /// If the index is out of bounds, the function returns `None`.
+    pub fn remove_row(&mut self, index: usize) -> Option<Vec<T>> {
+        if index >= self.columm_size {
+            return None;
+        }
+        let start: usize = index * self.row_size;
+        let row: Vec<T> = self.data.drain(start..start + self.row_size).collect();
+        self.columm_size -= 1;
+        if self.columm_size == 0 {
+            self.row_size = 0;
+        }
+        Some(row)
+    }
 
-pub fn h_hadamard<I, T>(vec1: &[I], vec2: &[T]) -> Vec<f64> 
-where
-    T: Copy + Into<f64>,
-    I: Copy + Into<f64>,
-{
-    let mut new_vec: Vec<f64> = Vec::new();
-    if vec1.len() != vec2.len() {
-        panic!("from: h_hadamard, vectors must have the same length");
+    /// Removes the column at the given index and returns it, moving the columns after it left by one.
+    /// If the index is out of bounds, the function returns `None`.
+    pub fn remove_col(&mut self, index: usize) -> Option<Vec<T>> {
+        if index >= self.row_size {
+            return None;
+        }
+        let mut col: Vec<T> = Vec::with_capacity(self.columm_size);
+        let mut data: Vec<T> = Vec::with_capacity(self.data.len() - self.columm_size);
+        // Rebuild the data in a single pass, picking the removed column out of each row.
+        for (i, value) in self.data.iter().enumerate() {
+            if i % self.row_size == index {
+                col.push(*value);
+            } else {
+                data.push(*value);
+            }
+        }
+        self.data = data;
+        self.row_size -= 1;
+        if self.row_size == 0 {
+            self.columm_size = 0;
+        }
+        Some(col)
     }
-    for (a, b) in zip(vec1, vec2) {
-        new_vec.push((*a).into() * (*b).into())
+
+    /// Sets the value at the specified row and column indices.
+    /// Returns an error if the indices are out of bounds.
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Result<(), String> {
+        if row >= self.columm_size || col >= self.row_size {
+            return Err(format!(
+                "index ({}, {}) is out of bounds for a {}x{} matrix. from: HMatrix, set()",
+                row, col, self.columm_size, self.row_size
+            ));
+        }
+        self.data[row * self.row_size + col] = value;
+        Ok(())
     }
-    return new_vec;
-}
 
+    /// Swaps two rows in place. Returns an error if either index is out of bounds.
+    pub fn swap_rows(&mut self, row1: usize, row2: usize) -> Result<(), String> {
+        if row1 >= self.columm_size || row2 >= self.columm_size {
+            return Err(format!(
+                "row indices ({}, {}) are out of bounds for a matrix with {} rows. from: HMatrix, swap_rows()",
+                row1, row2, self.columm_size
+            ));
+        }
+        if row1 != row2 {
+            let (first, second) = (row1.min(row2), row1.max(row2));
+            let (top, bottom) = self.data.split_at_mut(second * self.row_size);
+            top[first * self.row_size..(first + 1) * self.row_size].swap_with_slice(&mut bottom[..self.row_size]);
+        }
+        Ok(())
+    }
 
-/// Calculates the dot product of two vectors. The vectors must have the same length.
-/// Formula: C = A · B, where C = Σ(A[i] * B[i])
-///
-/// Example:
-/// 
-/// let vec1 = vec![1.0, 2.0, 3.0];
-/// let vec2 = vec![4.0, 5.0, 6.0];
-/// let result = h_dot(&vec1, &vec2);
-/// assert_eq!(result, 32.0);
-/// The dot product is calculated as (1*4) + (2*5) + (3*6) = 4 + 10 + 18 = 32.0.
+    /// Borrows the row at the given index as a slice, without copying it like `get_row` does.
+    /// If the index is out of bounds, the function returns `None`.
+    pub fn row(&self, index: usize) -> Option<&[T]> {
+        if index >= self.columm_size {
+            return None;
+        }
+        Some(&self.data[index * self.row_size..(index + 1) * self.row_size])
+    }
 
-pub fn h_dot<A, B>(vec1: &[A], vec2: &[B]) -> f64 
-where 
-    A: Copy + Into<f64>,
-    B: Copy + Into<f64>,
-{
-    if vec1.len() != vec2.len() {
-        panic!("h_dot: the two vectors do not have the same length");
+    /// Mutably borrows the row at the given index as a slice.
+    /// If the index is out of bounds, the function returns `None`.
+    pub fn row_mut(&mut self, index: usize) -> Option<&mut [T]> {
+        if index >= self.columm_size {
+            return None;
+        }
+        Some(&mut self.data[index * self.row_size..(index + 1) * self.row_size])
     }
-    let mut sum: f64 = 0.0;
-    for (a, b) in zip(vec1, vec2) {
-        sum += (*a).into()*(*b).into();
+
+    /// Returns an iterator over the elements of the column at the given index, from top to bottom,
+    /// without copying it like `get_col` does. If the index is out of bounds, the function returns `None`.
+    pub fn col(&self, index: usize) -> Option<impl Iterator<Item = &T>> {
+        if index >= self.row_size {
+            return None;
+        }
+        Some(self.data.iter().skip(index).step_by(self.row_size))
     }
-    return sum;
-}
 
+    /// Returns an iterator over the rows of the matrix, each borrowed as a slice.
+    ///
+    /// Example:
+    ///
+    /// let matrix = h_matrix![1, 2; 3, 4];
+    /// let sums: Vec<i32> = matrix.rows().map(|row| row.iter().sum()).collect();
+    /// assert_eq!(sums, vec![3, 7]);
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.data.chunks(self.row_size.max(1))
+    }
 
-/// Trait that provides the Euclidean magnitude (length) of a vector.
-///
-/// The magnitude of a vector `A` is defined as:
-/// ```text
-/// ||A|| = sqrt(A[0]^2 + A[1]^2 + ... + A[n]^2)
-/// ```
-///
-/// This is simply the Euclidean norm and corresponds to the length of the
-/// vector in n‑dimensional space.
-///
-/// # Examples
-///
-/// 
-/// let v = vec![3.0, 4.0];
-/// assert_eq!(v.h_magnitude(), 5.0);
-/// 
-///
-/// The trait is implemented for slices whose element type can be converted into
-/// `f64`.
-pub trait Magnitude {
-    fn h_magnitude(&self) -> f64;
-}
+    /// Returns an iterator over the columns of the matrix, where each column is itself an iterator over its elements.
+    ///
+    /// Example:
+    ///
+    /// let matrix = h_matrix![1, 2; 3, 4];
+    /// let sums: Vec<i32> = matrix.cols().map(|col| col.sum()).collect();
+    /// assert_eq!(sums, vec![4, 6]);
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.row_size).map(move |c| self.data.iter().skip(c).step_by(self.row_size))
+    }
 
-impl<T> Magnitude for [T] 
-where 
-    T: Copy + Into<f64>,
-{
-    fn h_magnitude(&self) -> f64 {
-        let sum_of_squares: f64 = self.iter().map(|x| {
-            let v: f64 = (*x).into();
-            v * v
-        }).sum();
-        sum_of_squares.sqrt()
+    /// Returns an iterator over all elements in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
     }
-}
 
+    /// Returns a mutable iterator over all elements in row-major order.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.data.iter_mut()
+    }
 
+    /// Creates a new matrix of the same shape by applying `f` to every element.
+    ///
+    /// Example:
+    ///
+    /// let matrix = h_matrix![1, 2; 3, 4];
+    /// let squared = matrix.map(|x| (x * x) as f64);
+    /// squared is the 2x2 matrix: [[1.0, 4.0], [9.0, 16.0]]
+    pub fn map<U, F>(&self, f: F) -> HMatrix<U>
+    where
+        U: Copy,
+        F: FnMut(T) -> U,
+    {
+        HMatrix {
+            data: self.data.iter().copied().map(f).collect(),
+            row_size: self.row_size,
+            columm_size: self.columm_size,
+        }
+    }
 
-/// Adds two vectors element-wise. The vectors must have the same length.
-/// Formula: C = A + B, where C[i] = A[i] + B[i]
-///
-/// Example:
-/// 
-/// let vec1 = vec![1.0, 2.0, 3.0];
-/// let vec2 = vec![4.0, 5.0, 6.0];
-/// let result = h_vector_add(&vec1, &vec2);
-/// assert_eq!(result, vec![5.0, 7.0, 9.0]);
-/// The vector addition is calculated as (1+4, 2+5, 3+6) = (5.0, 7.0, 9.0).
+    /// Applies `f` to every element in place.
+    pub fn map_inplace<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T) -> T,
+    {
+        for x in self.data.iter_mut() {
+            *x = f(*x);
+        }
+    }
 
-pub fn h_vector_add<T, I>(vec1: &[T], vec2: &[I]) -> Vec<f64>
-where
-    T: Copy + Into<f64>,
-    I: Copy + Into<f64>,
-{
-    if vec1.len() != vec2.len() {
-        panic!("from: h_vector_add, the vectors do not have the same length");
+    /// Returns the dimensions of the matrix as `(rows, columns)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.columm_size, self.row_size)
     }
 
-    zip(vec1, vec2)
-        .map(|(a, b)| (*a).into() + (*b).into())
-        .collect()
-}
+    /// Returns the transpose of the matrix, where row `i` becomes column `i`.
+    /// An m x n matrix becomes an n x m matrix.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// let transposed = matrix.h_transpose();
+    /// transposed is the 3x2 matrix: [[1, 4], [2, 5], [3, 6]]
+    pub fn h_transpose(&self) -> HMatrix<T> {
+        let mut data: Vec<T> = Vec::with_capacity(self.data.len());
+        for c in 0..self.row_size {
+            for r in 0..self.columm_size {
+                data.push(self.data[r * self.row_size + c]);
+            }
+        }
+        HMatrix {
+            data,
+            row_size: self.columm_size,
+            columm_size: self.row_size,
+        }
+    }
 
-/// Subtracts the second vector from the first, element‑wise. Both vectors must have
-/// the same length.
-///
-/// Example:
-/// 
-/// let vec1 = vec![5.0, 7.0, 9.0];
-/// let vec2 = vec![1.0, 2.0, 3.0];
-/// let result = h_vector_sub(&vec1, &vec2);
-/// assert_eq!(result, vec![4.0, 5.0, 6.0]);
-/// 
-pub fn h_vector_sub<T, I>(vec1: &[T], vec2: &[I]) -> Vec<f64>
-where
-    T: Copy + Into<f64>,
-    I: Copy + Into<f64>,
-{
-    if vec1.len() != vec2.len() {
-        panic!("from: h_vector_sub, the vectors do not have the same length");
+    /// Concatenates two matrices side by side, `[A B]`. The matrices must have the same number of rows,
+    /// otherwise the function returns `None`. The 0x0 matrix is treated as having any number of rows, like in `add_col`,
+    /// but an `m x 0` matrix, such as a trivial null space, only stacks with matrices that have `m` rows.
+    ///
+    /// Example:
+    ///
+    /// let stacked = h_matrix![1; 3].hstack(&h_matrix![2; 4]).unwrap();
+    /// assert_eq!(stacked, h_matrix![1, 2; 3, 4]);
+    pub fn hstack(&self, other: &HMatrix<T>) -> Option<HMatrix<T>> {
+        if self.shape() == (0, 0) {
+            return Some(other.clone());
+        }
+        if other.shape() == (0, 0) {
+            return Some(self.clone());
+        }
+        if self.columm_size != other.columm_size {
+            return None;
+        }
+        let mut data: Vec<T> = Vec::with_capacity(self.data.len() + other.data.len());
+        // Rows are sliced by index, because either side may have no columns at all.
+        for r in 0..self.columm_size {
+            data.extend_from_slice(&self.data[r * self.row_size..(r + 1) * self.row_size]);
+            data.extend_from_slice(&other.data[r * other.row_size..(r + 1) * other.row_size]);
+        }
+        Some(HMatrix {
+            data,
+            row_size: self.row_size + other.row_size,
+            columm_size: self.columm_size,
+        })
     }
 
-    zip(vec1, vec2)
-        .map(|(a, b)| (*a).into() - (*b).into())
-        .collect()
+    /// Concatenates two matrices on top of each other, `[A; B]`. The matrices must have the same number of columns,
+    /// otherwise the function returns `None`. The 0x0 matrix is treated as having any number of columns, like in `add_row`,
+    /// but a `0 x n` matrix only stacks with matrices that have `n` columns.
+    ///
+    /// Example:
+    ///
+    /// let stacked = h_matrix![1, 2].vstack(&h_matrix![3, 4]).unwrap();
+    /// assert_eq!(stacked, h_matrix![1, 2; 3, 4]);
+    pub fn vstack(&self, other: &HMatrix<T>) -> Option<HMatrix<T>> {
+        if self.shape() == (0, 0) {
+            return Some(other.clone());
+        }
+        if other.shape() == (0, 0) {
+            return Some(self.clone());
+        }
+        if self.row_size != other.row_size {
+            return None;
+        }
+        let mut data: Vec<T> = self.data.clone();
+        data.extend_from_slice(&other.data);
+        Some(HMatrix {
+            data,
+            row_size: self.row_size,
+            columm_size: self.columm_size + other.columm_size,
+        })
+    }
 }
 
-/// Multiply every element of a slice by a scalar value.
-///
-/// This trait is implemented for slice types so that you can write
-/// `my_slice.h_vector_scalar_mult(k)` and receive a new `Vec<f64>` containing
-/// each element of `my_slice` multiplied by `k`.
-///
-/// # Example
-/// let v = vec![1.0, 2.0, 3.0];
-/// assert_eq!(v.h_vector_scalar_mult(2.0), vec![2.0, 4.0, 6.0]);
-
-pub trait VectorScalarMultiply<S>
+impl<T> HMatrix<T>
 where
-    S: Copy + Into<f64>,
-{
-    fn h_vector_scalar_mult(&self, scalar: S) -> Vec<f64>;
-}
-
-impl<S, T> VectorScalarMultiply<S> for [T]
-where 
-    S: Copy + Into<f64>,
     T: Copy + Into<f64>,
 {
-    fn h_vector_scalar_mult(&self, scalar: S) -> Vec<f64> {
-        self.iter().map(|x| (*x).into() * scalar.into()).collect()
+    /// Checks whether two matrices have the same shape and every pair of elements differs by at most `tolerance`.
+    /// This is the comparison to use for results of floating point computations, where `==` is too strict.
+    ///
+    /// Example:
+    ///
+    /// let a = h_matrix![1.0, 2.0; 3.0, 4.0];
+    /// let b = h_matrix![1.0, 2.0; 3.0, 4.0 + 1e-12];
+    /// assert!(a.h_approx_eq(&b, 1e-9));
+    pub fn h_approx_eq<U>(&self, other: &HMatrix<U>, tolerance: f64) -> bool
+    where
+        U: Copy + Into<f64>,
+    {
+        if self.shape() != other.shape() {
+            return false;
+        }
+        zip(&self.data, &other.data).all(|(a, b)| ((*a).into() - (*b).into()).abs() <= tolerance)
     }
-}
 
+    /// Calculates the determinant of a square matrix from its LU decomposition (see `h_lu`).
+    /// The determinant is the product of the pivots of `U`, with the sign flipped once for every row swap.
+    /// If the matrix is not square or is empty, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![2.0, 1.0], vec![4.0, 3.0]]).unwrap();
+    /// assert_eq!(matrix.h_determinant(), Some(2.0));
+    /// The determinant is calculated as (2*3) - (1*4) = 2.0.
+    pub fn h_determinant(&self) -> Option<f64> {
+        self.h_lu().map(|lu| lu.h_determinant())
+    }
 
+    /// Calculates the inverse of a square matrix from its LU decomposition (see `h_lu`),
+    /// by solving `AX = I` one column of the identity matrix at a time.
+    /// If the matrix is not square, is empty or is singular (a pivot is numerically zero), the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![4.0, 7.0], vec![2.0, 6.0]]).unwrap();
+    /// let inverse = matrix.h_inverse().unwrap();
+    /// inverse is the 2x2 matrix: [[0.6, -0.7], [-0.2, 0.4]]
+    pub fn h_inverse(&self) -> Option<HMatrix<f64>> {
+        self.h_lu()?.h_inverse()
+    }
 
-/// Divide every element of a slice by a scalar value.
-///
-/// Similar to [`VectorScalarMultiply`], this trait is implemented for slice types
-/// and returns a `Vec<f64>` containing each element divided by the provided
-/// scalar.
-///
-/// # Example
-///
-/// 
-/// let v = vec![4.0, 6.0, 8.0];
-/// assert_eq!(v.h_vector_scalar_div(2.0), vec![2.0, 3.0, 4.0]);
-/// The vector scalar division is calculated as (4/2, 6/2, 8/2) = (2.0, 3.0, 4.0).
-pub trait VectorScalarDivision<S>
+    /// Computes the LU decomposition of a square matrix with partial pivoting, so that `PA = LU`,
+    /// where `P` is a permutation matrix, `L` is unit lower triangular and `U` is upper triangular.
+    /// The decomposition can be reused to solve several systems and to compute the determinant
+    /// or inverse without eliminating the matrix again.
+    /// Singular matrices still have a decomposition, but solving with it returns `LinearSystemError::Singular`.
+    /// If the matrix is not square or is empty, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+    /// let lu = matrix.h_lu().unwrap();
+    /// let x = lu.h_solve(&[5.0, 11.0]).unwrap();
+    /// let y = lu.h_solve(&[3.0, 7.0]).unwrap();
+    /// x is approximately [1.0, 2.0] and y is approximately [1.0, 1.0].
+    pub fn h_lu(&self) -> Option<HLuDecomposition> {
+        if self.row_size != self.columm_size || self.data.is_empty() {
+            return None;
+        }
+        let n: usize = self.row_size;
+        let mut a: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        let tolerance: f64 = elimination_tolerance(&a, n, n);
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut swaps: usize = 0;
+
+        for k in 0..n {
+            let pivot_row: usize = partial_pivot(&a, n, k, k, n);
+            if pivot_row != k {
+                swap_rows_flat(&mut a, n, pivot_row, k);
+                permutation.swap(pivot_row, k);
+                swaps += 1;
+            }
+            let pivot: f64 = a[k * n + k];
+            if pivot.abs() <= tolerance {
+                // The whole column below the pivot is numerically zero, so there is nothing to eliminate.
+                for r in k + 1..n {
+                    a[r * n + k] = 0.0;
+                }
+                continue;
+            }
+            for r in k + 1..n {
+                let factor: f64 = a[r * n + k] / pivot;
+                a[r * n + k] = factor;
+                for c in k + 1..n {
+                    a[r * n + c] -= factor * a[k * n + c];
+                }
+            }
+        }
+
+        Some(HLuDecomposition {
+            lu: HMatrix {
+                data: a,
+                row_size: n,
+                columm_size: n,
+            },
+            permutation,
+            swaps,
+            tolerance,
+        })
+    }
+
+    /// Computes the QR decomposition `A = QR` using Householder reflections, where `Q` is an orthogonal
+    /// matrix (m x m) and `R` is upper triangular (m x n). The matrix can have any shape.
+    /// Householder reflections are used instead of Gram-Schmidt because they keep `Q` orthogonal
+    /// to machine precision. If the matrix is empty, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![3.0, 1.0], vec![4.0, 2.0]]).unwrap();
+    /// let qr = matrix.h_qr().unwrap();
+    /// qr.r() is upper triangular with |r[0][0]| = 5.0, the length of the first column.
+    pub fn h_qr(&self) -> Option<HQrDecomposition> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let m: usize = self.columm_size;
+        let n: usize = self.row_size;
+        let mut r: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        let tolerance: f64 = elimination_tolerance(&r, m, n);
+        let mut q: Vec<f64> = vec![0.0; m * m];
+        for i in 0..m {
+            q[i * m + i] = 1.0;
+        }
+
+        for k in 0..n.min(m.saturating_sub(1)) {
+            let x: Vec<f64> = (k..m).map(|i| r[i * n + k]).collect();
+            let norm: f64 = x.h_magnitude();
+            if norm == 0.0 {
+                continue;
+            }
+            let alpha: f64 = if x[0] > 0.0 { -norm } else { norm };
+            let mut v: Vec<f64> = x;
+            v[0] -= alpha;
+            let v_norm_squared: f64 = h_dot(&v, &v);
+            if v_norm_squared == 0.0 {
+                continue;
+            }
+
+            // R = H R, only rows k.. and columns k.. are affected.
+            for c in k..n {
+                let column: Vec<f64> = (k..m).map(|i| r[i * n + c]).collect();
+                let s: f64 = 2.0 * h_dot(&v, &column) / v_norm_squared;
+                for i in k..m {
+                    r[i * n + c] -= s * v[i - k];
+                }
+            }
+            // Q = Q H, only columns k.. are affected.
+            for row in 0..m {
+                let s: f64 = 2.0 * h_dot(&q[row * m + k..(row + 1) * m], &v) / v_norm_squared;
+                for i in k..m {
+                    q[row * m + i] -= s * v[i - k];
+                }
+            }
+            r[k * n + k] = alpha;
+            for i in k + 1..m {
+                r[i * n + k] = 0.0;
+            }
+        }
+
+        Some(HQrDecomposition {
+            q: HMatrix {
+                data: q,
+                row_size: m,
+                columm_size: m,
+            },
+            r: HMatrix {
+                data: r,
+                row_size: n,
+                columm_size: m,
+            },
+            tolerance,
+        })
+    }
+
+    /// Computes the Cholesky decomposition `A = LLᵀ` of a symmetric positive-definite matrix,
+    /// where `L` is lower triangular with a positive diagonal. It is about twice as fast as LU
+    /// and is the standard way to solve systems with covariance and other positive-definite matrices.
+    /// If the matrix is not square, not symmetric or not positive-definite, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![4.0, 2.0], vec![2.0, 3.0]]).unwrap();
+    /// let cholesky = matrix.h_cholesky().unwrap();
+    /// cholesky.l() is the 2x2 matrix: [[2.0, 0.0], [1.0, 1.41421...]]
+    pub fn h_cholesky(&self) -> Option<HCholeskyDecomposition> {
+        if self.row_size != self.columm_size || self.data.is_empty() {
+            return None;
+        }
+        let n: usize = self.row_size;
+        let a: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        let tolerance: f64 = elimination_tolerance(&a, n, n);
+        if !is_symmetric_flat(&a, n, tolerance) {
+            return None;
+        }
+
+        let mut l: Vec<f64> = vec![0.0; n * n];
+        for j in 0..n {
+            let diagonal: f64 = a[j * n + j] - h_dot(&l[j * n..j * n + j], &l[j * n..j * n + j]);
+            if diagonal <= tolerance {
+                return None;
+            }
+            let l_jj: f64 = diagonal.sqrt();
+            l[j * n + j] = l_jj;
+            for i in j + 1..n {
+                l[i * n + j] = (a[i * n + j] - h_dot(&l[i * n..i * n + j], &l[j * n..j * n + j])) / l_jj;
+            }
+        }
+
+        Some(HCholeskyDecomposition {
+            l: HMatrix {
+                data: l,
+                row_size: n,
+                columm_size: n,
+            },
+        })
+    }
+
+    /// Checks whether the matrix is symmetric and positive-definite, meaning `xᵀAx > 0` for every non-zero `x`.
+    /// This is done by attempting a Cholesky decomposition, which succeeds exactly for such matrices.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![2.0, -1.0], vec![-1.0, 2.0]]).unwrap();
+    /// assert!(matrix.h_is_positive_definite());
+    pub fn h_is_positive_definite(&self) -> bool {
+        self.h_cholesky().is_some()
+    }
+
+    /// Computes the eigenvalues and eigenvectors of a symmetric matrix using the cyclic Jacobi method.
+    /// Every sweep applies plane rotations that zero the off-diagonal entries one at a time, until the
+    /// matrix is diagonal to machine precision. The diagonal then holds the eigenvalues, and the product
+    /// of the rotations holds the eigenvectors as columns.
+    /// The eigenvalues are sorted in descending order, which is the order used for principal component analysis.
+    /// If the matrix is not square or not symmetric, or the iteration does not converge, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![2.0, 1.0], vec![1.0, 2.0]]).unwrap();
+    /// let eigen = matrix.h_symmetric_eigen().unwrap();
+    /// eigen.eigenvalues() is approximately [3.0, 1.0], and the first column of eigen.eigenvectors()
+    /// is parallel to (1, 1).
+    pub fn h_symmetric_eigen(&self) -> Option<HEigenDecomposition> {
+        if self.row_size != self.columm_size || self.data.is_empty() {
+            return None;
+        }
+        let n: usize = self.row_size;
+        let mut a: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        if !is_symmetric_flat(&a, n, elimination_tolerance(&a, n, n)) {
+            return None;
+        }
+        let mut v: Vec<f64> = vec![0.0; n * n];
+        for i in 0..n {
+            v[i * n + i] = 1.0;
+        }
+
+        let norm_squared: f64 = h_dot(&a, &a);
+        let mut converged: bool = false;
+        for _ in 0..100 {
+            let off_diagonal: f64 = (0..n)
+                .flat_map(|p| (p + 1..n).map(move |q| (p, q)))
+                .map(|(p, q)| a[p * n + q] * a[p * n + q])
+                .sum();
+            if off_diagonal <= f64::EPSILON * f64::EPSILON * norm_squared {
+                converged = true;
+                break;
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    let a_pq: f64 = a[p * n + q];
+                    if a_pq == 0.0 {
+                        continue;
+                    }
+                    let theta: f64 = (a[q * n + q] - a[p * n + p]) / (2.0 * a_pq);
+                    let t: f64 = if theta >= 0.0 { 1.0 } else { -1.0 } / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c: f64 = 1.0 / (t * t + 1.0).sqrt();
+                    let s: f64 = t * c;
+                    for k in 0..n {
+                        let a_kp: f64 = a[k * n + p];
+                        let a_kq: f64 = a[k * n + q];
+                        a[k * n + p] = c * a_kp - s * a_kq;
+                        a[k * n + q] = s * a_kp + c * a_kq;
+                    }
+                    for k in 0..n {
+                        let a_pk: f64 = a[p * n + k];
+                        let a_qk: f64 = a[q * n + k];
+                        a[p * n + k] = c * a_pk - s * a_qk;
+                        a[q * n + k] = s * a_pk + c * a_qk;
+                    }
+                    for k in 0..n {
+                        let v_kp: f64 = v[k * n + p];
+                        let v_kq: f64 = v[k * n + q];
+                        v[k * n + p] = c * v_kp - s * v_kq;
+                        v[k * n + q] = s * v_kp + c * v_kq;
+                    }
+                }
+            }
+        }
+        if !converged {
+            return None;
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| a[j * n + j].total_cmp(&a[i * n + i]));
+        let eigenvalues: Vec<f64> = order.iter().map(|&i| a[i * n + i]).collect();
+        let mut eigenvectors: Vec<f64> = vec![0.0; n * n];
+        for (new_col, &old_col) in order.iter().enumerate() {
+            for r in 0..n {
+                eigenvectors[r * n + new_col] = v[r * n + old_col];
+            }
+        }
+
+        Some(HEigenDecomposition {
+            eigenvalues,
+            eigenvectors: HMatrix {
+                data: eigenvectors,
+                row_size: n,
+                columm_size: n,
+            },
+        })
+    }
+
+    /// Converts every element into an `HComplex` with a zero imaginary part.
+    pub fn to_complex(&self) -> HMatrix<HComplex> {
+        self.map(|x| HComplex::from(x.into()))
+    }
+
+    /// Computes all eigenvalues like `h_eigenvalues`, but as complex numbers, with each complex
+    /// conjugate pair listed as two eigenvalues (positive imaginary part first).
+    /// If the matrix is not square or the iteration does not converge, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let rotation = h_matrix![0.0, -1.0; 1.0, 0.0];
+    /// let eigenvalues = rotation.h_complex_eigenvalues().unwrap();
+    /// assert_eq!(eigenvalues, vec![HComplex::new(0.0, 1.0), HComplex::new(0.0, -1.0)]);
+    pub fn h_complex_eigenvalues(&self) -> Option<Vec<HComplex>> {
+        let mut eigenvalues: Vec<HComplex> = Vec::new();
+        for eigenvalue in self.h_eigenvalues()? {
+            match eigenvalue {
+                HEigenvalue::Real(re) => eigenvalues.push(HComplex::from(re)),
+                HEigenvalue::ComplexPair { re, im } => {
+                    eigenvalues.push(HComplex::new(re, im));
+                    eigenvalues.push(HComplex::new(re, -im));
+                }
+            }
+        }
+        Some(eigenvalues)
+    }
+
+    /// Computes all eigenvalues of a general real square matrix. The matrix is reduced to upper Hessenberg form
+    /// and then iterated with the Francis double-shift QR algorithm, which keeps all arithmetic real.
+    /// Complex eigenvalues of a real matrix always come in conjugate pairs `re ± im·i`, and each pair is reported
+    /// once as `HEigenvalue::ComplexPair`. Symmetric matrices are passed to `h_symmetric_eigen` instead,
+    /// since their eigenvalues are always real.
+    /// The eigenvalues are sorted by descending real part, then by imaginary part.
+    /// If the matrix is not square or the iteration does not converge, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let rotation = HMatrix::new_from_rows(&[vec![0.0, -1.0], vec![1.0, 0.0]]).unwrap();
+    /// let eigenvalues = rotation.h_eigenvalues().unwrap();
+    /// eigenvalues is [HEigenvalue::ComplexPair { re: 0.0, im: 1.0 }], the pair ±i.
+    pub fn h_eigenvalues(&self) -> Option<Vec<HEigenvalue>> {
+        if self.row_size != self.columm_size || self.data.is_empty() {
+            return None;
+        }
+        let n: usize = self.row_size;
+        let mut a: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        if is_symmetric_flat(&a, n, elimination_tolerance(&a, n, n)) {
+            let eigen: HEigenDecomposition = self.h_symmetric_eigen()?;
+            return Some(eigen.eigenvalues.into_iter().map(HEigenvalue::Real).collect());
+        }
+
+        hessenberg_reduce(&mut a, n);
+        let (real, imaginary) = hessenberg_qr(&mut a, n)?;
+
+        let mut eigenvalues: Vec<HEigenvalue> = Vec::new();
+        for (re, im) in zip(real, imaginary) {
+            if im == 0.0 {
+                eigenvalues.push(HEigenvalue::Real(re));
+            } else if im > 0.0 {
+                eigenvalues.push(HEigenvalue::ComplexPair { re, im });
+            }
+        }
+        eigenvalues.sort_by(|x, y| {
+            let (x_re, x_im) = x.parts();
+            let (y_re, y_im) = y.parts();
+            y_re.total_cmp(&x_re).then(x_im.total_cmp(&y_im))
+        });
+        Some(eigenvalues)
+    }
+
+    /// Computes the thin singular value decomposition `A = UΣVᵀ` using the one-sided Jacobi method.
+    /// For an m x n matrix with k = min(m, n), `U` is m x k with orthonormal columns, `Σ` holds the k
+    /// non-negative singular values in descending order, and `Vᵀ` is k x n with orthonormal rows.
+    /// Jacobi rotations are applied to pairs of columns until all columns are orthogonal, which gives
+    /// singular values with high relative accuracy, also for rank-deficient matrices.
+    /// If the matrix is empty or the iteration does not converge, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![3.0, 0.0], vec![4.0, 5.0]]).unwrap();
+    /// let svd = matrix.h_svd().unwrap();
+    /// svd.singular_values() is approximately [6.7082, 2.2361], which are √45 and √5.
+    pub fn h_svd(&self) -> Option<HSvd> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let m: usize = self.columm_size;
+        let n: usize = self.row_size;
+        let values: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+
+        // The one-sided Jacobi method needs at least as many rows as columns, so a wide
+        // matrix is decomposed as its transpose, and U and V swap roles afterwards.
+        let transposed: bool = m < n;
+        let (rows, cols): (usize, usize) = if transposed { (n, m) } else { (m, n) };
+        let mut u_cols: Vec<Vec<f64>> = (0..cols)
+            .map(|c| {
+                (0..rows)
+                    .map(|r| if transposed { values[c * n + r] } else { values[r * n + c] })
+                    .collect()
+            })
+            .collect();
+        let mut v_cols: Vec<Vec<f64>> = (0..cols)
+            .map(|c| (0..cols).map(|r| if r == c { 1.0 } else { 0.0 }).collect())
+            .collect();
+
+        let mut converged: bool = false;
+        for _ in 0..60 {
+            let mut rotated: bool = false;
+            for p in 0..cols {
+                for q in p + 1..cols {
+                    let alpha: f64 = h_dot(&u_cols[p], &u_cols[p]);
+                    let beta: f64 = h_dot(&u_cols[q], &u_cols[q]);
+                    let gamma: f64 = h_dot(&u_cols[p], &u_cols[q]);
+                    if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+                    let zeta: f64 = (beta - alpha) / (2.0 * gamma);
+                    let t: f64 = if zeta >= 0.0 { 1.0 } else { -1.0 } / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                    let c: f64 = 1.0 / (1.0 + t * t).sqrt();
+                    let s: f64 = c * t;
+                    rotate_columns(&mut u_cols, p, q, c, s);
+                    rotate_columns(&mut v_cols, p, q, c, s);
+                }
+            }
+            if !rotated {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return None;
+        }
+
+        let mut singular_values: Vec<f64> = u_cols.iter().map(|col| col.h_magnitude()).collect();
+        let mut order: Vec<usize> = (0..cols).collect();
+        order.sort_by(|&i, &j| singular_values[j].total_cmp(&singular_values[i]));
+        singular_values = order.iter().map(|&i| singular_values[i]).collect();
+        let mut left: Vec<Vec<f64>> = order.iter().map(|&i| u_cols[i].clone()).collect();
+        let right: Vec<Vec<f64>> = order.iter().map(|&i| v_cols[i].clone()).collect();
+
+        let tolerance: f64 = rows as f64 * f64::EPSILON * singular_values[0];
+        for (i, sigma) in singular_values.iter().enumerate() {
+            if *sigma > tolerance && *sigma > 0.0 {
+                left[i] = left[i].h_vector_scalar_div(*sigma);
+            } else {
+                left[i] = orthonormal_complement(&left[..i], rows);
+            }
+        }
+
+        // `left` holds the columns of U and `right` the columns of V (or the other way around when transposed).
+        let (u_columns, v_columns): (Vec<Vec<f64>>, Vec<Vec<f64>>) = if transposed { (right, left) } else { (left, right) };
+        let k: usize = cols;
+        let mut u: Vec<f64> = vec![0.0; m * k];
+        for (c, column) in u_columns.iter().enumerate() {
+            for r in 0..m {
+                u[r * k + c] = column[r];
+            }
+        }
+        let mut vt: Vec<f64> = Vec::with_capacity(k * n);
+        for column in &v_columns {
+            vt.extend_from_slice(column);
+        }
+
+        Some(HSvd {
+            u: HMatrix {
+                data: u,
+                row_size: k,
+                columm_size: m,
+            },
+            singular_values,
+            vt: HMatrix {
+                data: vt,
+                row_size: n,
+                columm_size: k,
+            },
+        })
+    }
+
+    /// Calculates the Moore-Penrose pseudo-inverse `A⁺ = VΣ⁺Uᵀ` from the singular value decomposition,
+    /// where `Σ⁺` inverts the singular values above the default tolerance and sets the rest to zero.
+    /// Unlike `h_inverse`, it exists for every matrix, including non-square and rank-deficient ones,
+    /// and `A⁺b` is the minimum-norm least-squares solution of `Ax = b`.
+    /// If the singular value decomposition fails, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+    /// let pseudo_inverse = matrix.h_pseudo_inverse().unwrap();
+    /// pseudo_inverse is the 2x2 matrix: [[0.04, 0.08], [0.08, 0.16]], even though the matrix is singular.
+    pub fn h_pseudo_inverse(&self) -> Option<HMatrix<f64>> {
+        Some(self.h_svd()?.h_pseudo_inverse())
+    }
+
+    /// Calculates the 2-norm condition number, the ratio between the largest and smallest singular value.
+    /// It measures how much errors in `b` can be amplified in the solution of `Ax = b`: a value near 1 is
+    /// well-conditioned, and a singular matrix has an infinite condition number.
+    /// If the singular value decomposition fails, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![2.0, 0.0], vec![0.0, 0.5]]).unwrap();
+    /// assert_eq!(matrix.h_condition_number(), Some(4.0));
+    pub fn h_condition_number(&self) -> Option<f64> {
+        Some(self.h_svd()?.h_condition_number())
+    }
+
+    /// Calculates the numerical rank, the number of singular values above a tolerance.
+    /// If `tolerance` is `None`, the default `max(m, n) * ε * σ_max` is used. The singular value decomposition
+    /// is the most reliable way to decide the rank of noisy data, since a tolerance can be chosen to match the noise.
+    /// An empty matrix, or one whose singular value decomposition fails, has rank 0.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.000001]]).unwrap();
+    /// assert_eq!(matrix.h_numerical_rank(None), 2);
+    /// assert_eq!(matrix.h_numerical_rank(Some(1e-3)), 1);
+    pub fn h_numerical_rank(&self, tolerance: Option<f64>) -> usize {
+        match self.h_svd() {
+            Some(svd) => svd.h_rank(tolerance),
+            None => 0,
+        }
+    }
+
+    /// Calculates the rank of the matrix, which is the number of linearly independent rows (or columns).
+    /// The matrix is reduced to row echelon form using Gaussian elimination with partial pivoting,
+    /// and the rank is the number of pivots that are not numerically zero. The matrix does not need to be square.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+    /// assert_eq!(matrix.h_rank(), 1);
+    /// The second row is twice the first, so only one row is linearly independent.
+    pub fn h_rank(&self) -> usize {
+        let rows: usize = self.columm_size;
+        let cols: usize = self.row_size;
+        let mut a: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        let tolerance: f64 = elimination_tolerance(&a, rows, cols);
+
+        let mut rank: usize = 0;
+        for c in 0..cols {
+            if rank == rows {
+                break;
+            }
+            let pivot_row: usize = partial_pivot(&a, cols, c, rank, rows);
+            if a[pivot_row * cols + c].abs() <= tolerance {
+                continue;
+            }
+            swap_rows_flat(&mut a, cols, pivot_row, rank);
+            let pivot: f64 = a[rank * cols + c];
+            for r in rank + 1..rows {
+                let factor: f64 = a[r * cols + c] / pivot;
+                for k in c..cols {
+                    a[r * cols + k] -= factor * a[rank * cols + k];
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+
+    /// Returns an orthonormal basis of the column space (the span of the columns), as the columns of a new matrix.
+    /// The basis is computed with modified Gram-Schmidt (see `h_gram_schmidt`), so columns that depend on
+    /// earlier columns are skipped and the number of basis vectors is the rank of the matrix.
+    /// If the matrix is zero, the column space is trivial and the function returns a `rows x 0` matrix.
+    ///
+    /// Example:
+    ///
+    /// let matrix = h_matrix![1.0, 2.0; 0.0, 0.0; 0.0, 0.0];
+    /// let basis = matrix.h_column_space();
+    /// assert_eq!(basis, h_matrix![1.0; 0.0; 0.0]);
+    pub fn h_column_space(&self) -> HMatrix<f64> {
+        let cols: Vec<Vec<f64>> = self.cols().map(|col| col.map(|x| (*x).into()).collect()).collect();
+        matrix_from_basis(modified_gram_schmidt(cols), self.columm_size)
+    }
+
+    /// Returns an orthonormal basis of the null space, every vector `x` with `Ax = 0`, as the columns of a new matrix.
+    /// The null space is the orthogonal complement of the row space, so it is found by orthonormalizing the rows
+    /// with modified Gram-Schmidt and then completing that basis with standard basis vectors.
+    /// There are `columns - rank` basis vectors. If the columns are linearly independent,
+    /// the null space is trivial and the function returns a `columns x 0` matrix.
+    ///
+    /// Example:
+    ///
+    /// let matrix = h_matrix![1.0, 1.0; 2.0, 2.0];
+    /// let null_space = matrix.h_null_space();
+    /// null_space is the 2x1 matrix [[-0.7071], [0.7071]] (up to sign), since x = -y solves both equations.
+    pub fn h_null_space(&self) -> HMatrix<f64> {
+        let rows: Vec<Vec<f64>> = self.rows().map(|row| row.iter().map(|x| (*x).into()).collect()).collect();
+        let row_space: Vec<Vec<f64>> = modified_gram_schmidt(rows);
+        matrix_from_basis(orthogonal_complement(row_space, self.row_size), self.row_size)
+    }
+
+    /// Returns `true` if the columns of the matrix are linearly independent, that is if `Ax = 0` only for `x = 0`.
+    /// See `h_are_linearly_independent` for the tolerance used.
+    pub fn h_is_linearly_independent(&self) -> bool {
+        self.h_column_space().row_size == self.row_size
+    }
+
+    /// Calculates the trace, the sum of the diagonal elements, which equals the sum of the eigenvalues.
+    /// If the matrix is not square, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// assert_eq!(h_matrix![1, 2; 3, 4].h_trace(), Some(5.0));
+    pub fn h_trace(&self) -> Option<f64> {
+        if self.row_size != self.columm_size {
+            return None;
+        }
+        Some((0..self.row_size).map(|i| self.data[i * self.row_size + i].into()).sum())
+    }
+
+    /// Calculates the Frobenius norm `sqrt(Σ a[i][j]^2)`, the Euclidean magnitude of all elements.
+    pub fn h_frobenius_norm(&self) -> f64 {
+        self.data.h_magnitude()
+    }
+
+    /// Calculates the 1-norm, the largest sum of absolute values in a column.
+    pub fn h_one_norm(&self) -> f64 {
+        self.cols()
+            .map(|col| col.map(|x| (*x).into().abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+
+    /// Calculates the ∞-norm, the largest sum of absolute values in a row.
+    pub fn h_infinity_norm(&self) -> f64 {
+        self.rows()
+            .map(|row| row.iter().map(|x| (*x).into().abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+
+    /// Raises a square matrix to an integer power by repeated squaring, which takes about `2 * log2(exponent)`
+    /// multiplications instead of `exponent`. `h_pow(0)` is the identity matrix, and a negative exponent
+    /// raises the inverse to the corresponding positive power.
+    /// If the matrix is not square, or the exponent is negative and the matrix is singular, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let transition = h_matrix![0.9, 0.1; 0.5, 0.5];
+    /// let after_ten_steps = transition.h_pow(10).unwrap();
+    /// Row i of after_ten_steps is the distribution over the states of a Markov chain that started in state i.
+    pub fn h_pow(&self, exponent: i32) -> Option<HMatrix<f64>> {
+        if self.row_size != self.columm_size {
+            return None;
+        }
+        let mut base: HMatrix<f64> = if exponent < 0 {
+            self.h_inverse()?
+        } else {
+            self.map(|x| x.into())
+        };
+        let mut result: HMatrix<f64> = identity_f64(self.row_size);
+        let mut remaining: u32 = exponent.unsigned_abs();
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = &result * &base;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                base = &base * &base;
+            }
+        }
+        Some(result)
+    }
+
+    /// Calculates the matrix exponential `e^A = I + A + A²/2! + A³/3! + ...`, which solves the linear
+    /// ODE system `x' = Ax` as `x(t) = e^(At) x(0)`.
+    /// It uses scaling and squaring with a [6/6] Padé approximant: the matrix is divided by `2^s` until its
+    /// ∞-norm is at most 1/2, the Padé approximant is evaluated, and the result is squared `s` times.
+    /// If the matrix is not square, is empty, has an infinite or NaN element, or has an ∞-norm so large
+    /// (at least 2^1022) that the scaling factor overflows, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let rotation_generator = h_matrix![0.0, -1.0; 1.0, 0.0];
+    /// let rotation = (&rotation_generator * std::f64::consts::FRAC_PI_2).h_exp().unwrap();
+    /// rotation is approximately [[0.0, -1.0], [1.0, 0.0]], a quarter turn.
+    pub fn h_exp(&self) -> Option<HMatrix<f64>> {
+        if self.row_size != self.columm_size || self.data.is_empty() {
+            return None;
+        }
+        let n: usize = self.row_size;
+        // An infinite norm would ask for an unbounded number of squarings, and `f64::max` in the norm skips NaN.
+        if self.data.iter().any(|x| !(*x).into().is_finite()) {
+            return None;
+        }
+        let norm: f64 = self.h_infinity_norm();
+        let squarings: i32 = if norm > 0.5 { (norm.log2() + 2.0).floor() as i32 } else { 0 };
+        // Near f64::MAX the row sums overflow, or 2^squarings does, which would scale the matrix down to zero.
+        if !norm.is_finite() || squarings >= f64::MAX_EXP {
+            return None;
+        }
+        let a: HMatrix<f64> = self.map(|x| x.into() / 2f64.powi(squarings));
+
+        const Q: i32 = 6;
+        let mut c: f64 = 0.5;
+        let mut power: HMatrix<f64> = a.clone();
+        let mut numerator: HMatrix<f64> = &identity_f64(n) + &(&a * c);
+        let mut denominator: HMatrix<f64> = &identity_f64(n) - &(&a * c);
+        for k in 2..=Q {
+            c *= (Q - k + 1) as f64 / (k * (2 * Q - k + 1)) as f64;
+            power = &a * &power;
+            numerator = &numerator + &(&power * c);
+            if k % 2 == 0 {
+                denominator = &denominator + &(&power * c);
+            } else {
+                denominator = &denominator - &(&power * c);
+            }
+        }
+
+        let mut result: HMatrix<f64> = denominator.h_solve_multiple(&numerator).ok()?;
+        for _ in 0..squarings {
+            result = &result * &result;
+        }
+        Some(result)
+    }
+
+    /// Calculates the Kronecker product `A ⊗ B`, the block matrix where block `(i, j)` is `a[i][j] * B`.
+    /// An `m x n` matrix and a `p x q` matrix give an `mp x nq` matrix.
+    ///
+    /// Example:
+    ///
+    /// let product = h_matrix![1, 2].h_kronecker(&h_matrix![1; 10]);
+    /// assert_eq!(product, h_matrix![1.0, 2.0; 10.0, 20.0]);
+    pub fn h_kronecker<U>(&self, other: &HMatrix<U>) -> HMatrix<f64>
+    where
+        U: Copy + Into<f64>,
+    {
+        let (p, q): (usize, usize) = other.shape();
+        HMatrix::from_fn(self.columm_size * p, self.row_size * q, |r, c| {
+            self.data[(r / p) * self.row_size + c / q].into() * other.data[(r % p) * q + c % q].into()
+        })
+    }
+
+    /// Solves the linear system `Ax = b`, where `A` is this matrix and `b` is the right-hand side vector.
+    /// The system is reduced with Gaussian elimination with partial pivoting and solved by back substitution.
+    /// The right-hand side must have one entry per row of the matrix.
+    ///
+    /// A system with more equations than unknowns is accepted as long as it is consistent, which is checked
+    /// by applying the matrix to the solution with `h_linear_transform` and comparing the result with `b`.
+    /// Otherwise the function returns a `LinearSystemError` describing why there is no unique solution.
+    /// A system with more equations than unknowns that has no solution is reported as `Overdetermined`,
+    /// even if its columns are also linearly dependent; `Underdetermined` means the system is consistent
+    /// but has infinitely many solutions.
+    ///
+    /// Example:
+    ///
+    /// let a = HMatrix::new_from_rows(&[vec![2.0, 1.0], vec![1.0, 3.0]]).unwrap();
+    /// let x = a.h_solve(&[3.0, 5.0]).unwrap();
+    /// x is approximately [0.8, 1.4]
+    /// The solution satisfies 2*0.8 + 1*1.4 = 3 and 1*0.8 + 3*1.4 = 5.
+    pub fn h_solve<S>(&self, rhs: &[S]) -> Result<Vec<f64>, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        if rhs.len() != self.columm_size {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: self.columm_size,
+                rhs_len: rhs.len(),
+            });
+        }
+        let rhs: Vec<f64> = rhs.iter().map(|x| (*x).into()).collect();
+        self.solve_augmented(&rhs, 1)
+    }
+
+    /// Solves the linear system `AX = B` for several right-hand sides at once. Every column of `rhs`
+    /// is a separate right-hand side, and the matching column of the returned matrix is its solution.
+    /// The elimination is only performed once, so this is cheaper than calling `h_solve` for each column.
+    /// `rhs` must have the same number of rows as the matrix, otherwise `LinearSystemError::DimensionMismatch` is returned.
+    ///
+    /// Example:
+    ///
+    /// let a = HMatrix::new_from_rows(&[vec![2.0, 0.0], vec![0.0, 4.0]]).unwrap();
+    /// let b = HMatrix::new_from_rows(&[vec![2.0, 4.0], vec![4.0, 8.0]]).unwrap();
+    /// let x = a.h_solve_multiple(&b).unwrap();
+    /// x is the 2x2 matrix: [[1.0, 2.0], [1.0, 2.0]]
+    pub fn h_solve_multiple<S>(&self, rhs: &HMatrix<S>) -> Result<HMatrix<f64>, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        if rhs.columm_size != self.columm_size {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: self.columm_size,
+                rhs_len: rhs.columm_size,
+            });
+        }
+        let values: Vec<f64> = rhs.data.iter().map(|x| (*x).into()).collect();
+        let data: Vec<f64> = self.solve_augmented(&values, rhs.row_size)?;
+        Ok(HMatrix {
+            data,
+            row_size: rhs.row_size,
+            columm_size: self.row_size,
+        })
+    }
+
+    /// Fits the linear model `y ≈ Ax` to the `observations` y in the least-squares sense, where this matrix
+    /// is the design matrix `A` with one row per observation and one column per coefficient.
+    /// The coefficients are found with the QR decomposition (see `h_qr`), which is more accurate than
+    /// solving the normal equations `AᵀAx = Aᵀy` because it does not square the condition number.
+    /// Include a column of ones in the design matrix to fit an intercept.
+    ///
+    /// Returns `LinearSystemError::DimensionMismatch` if there is not one observation per row,
+    /// and `LinearSystemError::Underdetermined` (or `Singular` for square matrices) if the columns are linearly dependent,
+    /// for example when there are fewer observations than coefficients.
+    ///
+    /// Example:
+    ///
+    /// let x = [0.0, 1.0, 2.0, 3.0];
+    /// let design = HMatrix::from_fn(4, 2, |i, j| if j == 0 { 1.0 } else { x[i] });
+    /// let fit = design.h_least_squares(&[1.0, 3.0, 5.0, 7.5]).unwrap();
+    /// fit.coefficients() is approximately [0.9, 2.15], the line y = 0.9 + 2.15x,
+    /// and fit.r_squared() is approximately 0.997.
+    pub fn h_least_squares<S>(&self, observations: &[S]) -> Result<HLeastSquares, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        if observations.len() != self.columm_size || self.data.is_empty() {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: self.columm_size,
+                rhs_len: observations.len(),
+            });
+        }
+        let coefficients: Vec<f64> = self.h_qr().unwrap().h_solve(observations)?;
+        let fitted_values: Vec<f64> = coefficients.h_linear_transform(self).unwrap();
+        let residuals: Vec<f64> = h_vector_sub(observations, &fitted_values);
+
+        let mean: f64 = observations.iter().map(|y| (*y).into()).sum::<f64>() / observations.len() as f64;
+        let residual_sum_of_squares: f64 = h_dot(&residuals, &residuals);
+        let total_sum_of_squares: f64 = observations.iter().map(|y| ((*y).into() - mean).powi(2)).sum();
+        let r_squared: f64 = if total_sum_of_squares == 0.0 {
+            // Constant observations: the fit explains everything only if it reproduces them exactly.
+            if residual_sum_of_squares == 0.0 { 1.0 } else { 0.0 }
+        } else {
+            1.0 - residual_sum_of_squares / total_sum_of_squares
+        };
+
+        Ok(HLeastSquares {
+            coefficients,
+            fitted_values,
+            residuals,
+            residual_sum_of_squares,
+            r_squared,
+        })
+    }
+
+    /// Shared implementation of `h_solve` and `h_solve_multiple`. `rhs` is a flat row-major matrix
+    /// with `rhs_cols` columns and one row per equation. Returns the solutions as a flat row-major
+    /// matrix with one row per unknown and one column per right-hand side.
+    fn solve_augmented(&self, rhs: &[f64], rhs_cols: usize) -> Result<Vec<f64>, LinearSystemError> {
+        let equations: usize = self.columm_size;
+        let unknowns: usize = self.row_size;
+        let width: usize = unknowns + rhs_cols;
+
+        let values: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        let tolerance: f64 = elimination_tolerance(&values, equations, unknowns);
+        let mut a: Vec<f64> = vec![0.0; equations * width];
+        for r in 0..equations {
+            a[r * width..r * width + unknowns].copy_from_slice(&values[r * unknowns..(r + 1) * unknowns]);
+            a[r * width + unknowns..(r + 1) * width].copy_from_slice(&rhs[r * rhs_cols..(r + 1) * rhs_cols]);
+        }
+
+        let mut rank: usize = 0;
+        for c in 0..unknowns {
+            if rank == equations {
+                break;
+            }
+            let pivot_row: usize = partial_pivot(&a, width, c, rank, equations);
+            if a[pivot_row * width + c].abs() <= tolerance {
+                continue;
+            }
+            swap_rows_flat(&mut a, width, pivot_row, rank);
+            let pivot: f64 = a[rank * width + c];
+            for r in rank + 1..equations {
+                let factor: f64 = a[r * width + c] / pivot;
+                for k in c..width {
+                    a[r * width + k] -= factor * a[rank * width + k];
+                }
+            }
+            rank += 1;
+        }
+
+        if rank < unknowns {
+            if equations == unknowns {
+                return Err(LinearSystemError::Singular);
+            }
+            // The rows below the rank were eliminated to zero, so their right-hand sides must be zero too,
+            // otherwise no solution exists at all.
+            let scale: f64 = rhs.iter().chain(values.iter()).fold(1.0, |acc: f64, v| acc.max(v.abs()));
+            let inconsistent: bool = (rank..equations).any(|r| {
+                a[r * width + unknowns..(r + 1) * width]
+                    .iter()
+                    .any(|v| v.abs() > f64::EPSILON.sqrt() * scale)
+            });
+            if equations > unknowns && inconsistent {
+                return Err(LinearSystemError::Overdetermined { equations, unknowns });
+            }
+            return Err(LinearSystemError::Underdetermined { rank, unknowns });
+        }
+
+        // With full column rank the first `unknowns` rows form an upper triangular system.
+        let mut solution: Vec<f64> = vec![0.0; unknowns * rhs_cols];
+        for j in 0..rhs_cols {
+            let mut x: Vec<f64> = vec![0.0; unknowns];
+            for i in (0..unknowns).rev() {
+                let row: &[f64] = &a[i * width + i + 1..i * width + unknowns];
+                x[i] = (a[i * width + unknowns + j] - h_dot(row, &x[i + 1..])) / a[i * width + i];
+            }
+
+            if equations > unknowns {
+                let b: Vec<f64> = (0..equations).map(|r| rhs[r * rhs_cols + j]).collect();
+                let scale: f64 = b.iter().chain(values.iter()).fold(1.0, |acc: f64, v| acc.max(v.abs()));
+                let residual: Vec<f64> = h_vector_sub(&x.h_linear_transform(self).unwrap(), &b);
+                if residual.iter().any(|r| r.abs() > f64::EPSILON.sqrt() * scale) {
+                    return Err(LinearSystemError::Overdetermined { equations, unknowns });
+                }
+            }
+
+            for i in 0..unknowns {
+                solution[i * rhs_cols + j] = x[i];
+            }
+        }
+        Ok(solution)
+    }
+}
+
+
+// Constructors that need the numbers zero and one. Every primitive number type implements `From<bool>`,
+// where `false` is zero and `true` is one, so this works for integer and floating point matrices alike.
+impl<T> HMatrix<T>
+where
+    T: Copy + Into<f64> + From<bool>,
+{
+    /// Creates a new `HMatrix` with `rows` rows and `cols` columns where every element is zero.
+    ///
+    /// Example:
+    ///
+    /// let matrix: HMatrix<f64> = HMatrix::zeros(2, 3);
+    /// matrix is the 2x3 matrix: [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        HMatrix {
+            data: vec![T::from(false); rows * cols],
+            row_size: cols,
+            columm_size: rows,
+        }
+    }
+
+    /// Creates the n x n identity matrix, with ones on the diagonal and zeros everywhere else.
+    ///
+    /// Example:
+    ///
+    /// let matrix: HMatrix<i32> = HMatrix::identity(2);
+    /// matrix is the 2x2 matrix: [[1, 0], [0, 1]]
+    pub fn identity(n: usize) -> Self {
+        HMatrix::from_fn(n, n, |i, j| T::from(i == j))
+    }
+
+    /// Creates a square matrix with the given values on the diagonal and zeros everywhere else.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HMatrix::from_diagonal(&[1.0, 2.0, 3.0]);
+    /// matrix is the 3x3 matrix: [[1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 3.0]]
+    pub fn from_diagonal(diagonal: &[T]) -> Self {
+        let n: usize = diagonal.len();
+        HMatrix::from_fn(n, n, |i, j| if i == j { diagonal[i] } else { T::from(false) })
+    }
+}
+
+
+/// Creates an `HMatrix` from a matrix literal, with the elements of a row separated by commas
+/// and the rows separated by semicolons. Panics if the rows do not all have the same length.
+///
+/// Example:
+///
+/// let matrix = h_matrix![1.0, 2.0, 3.0;
+///                        4.0, 5.0, 6.0];
+/// matrix is the 2x3 matrix: [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]
+#[macro_export]
+macro_rules! h_matrix {
+    ($($($x:expr),+ $(,)?);+ $(;)?) => {
+        $crate::linear_algebra::HMatrix::new_from_rows(&[$(vec![$($x),+]),+])
+            .expect("from: h_matrix!, every row must have the same number of elements")
+    };
+}
+
+
+/// Displays the matrix one row per line, with the columns right-aligned.
+/// The precision of the formatter is passed on to every element, so `format!("{:.2}", matrix)`
+/// prints floating point elements with two decimals.
+///
+/// Example:
+///
+/// let matrix = h_matrix![1.0, -2.5; 30.0, 4.0];
+/// println!("{:.1}", matrix);
+/// prints:
+/// [ 1.0 -2.5]
+/// [30.0  4.0]
+impl<T> fmt::Display for HMatrix<T>
+where
+    T: Copy + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self
+            .data
+            .iter()
+            .map(|x| match f.precision() {
+                Some(precision) => format!("{:.*}", precision, x),
+                None => format!("{}", x),
+            })
+            .collect();
+        let mut widths: Vec<usize> = vec![0; self.row_size];
+        for (i, cell) in cells.iter().enumerate() {
+            let col: usize = i % self.row_size;
+            widths[col] = widths[col].max(cell.chars().count());
+        }
+
+        for r in 0..self.columm_size {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "[")?;
+            for c in 0..self.row_size {
+                if c > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{:>width$}", cells[r * self.row_size + c], width = widths[c])?;
+            }
+            write!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+
+// The arithmetic operators convert every element to `f64`, like `h_linear_composition`, so matrices
+// of different element types can be combined. A dimension mismatch is a programming error, and
+// panics with a message that includes both shapes. Use `shape()` to check the dimensions beforehand.
+
+impl<T, U> Add<&HMatrix<U>> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    U: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    /// Adds two matrices element-wise. Panics if the matrices do not have the same shape.
+    fn add(self, other: &HMatrix<U>) -> HMatrix<f64> {
+        if self.shape() != other.shape() {
+            panic!(
+                "from: HMatrix Add, cannot add a {}x{} matrix and a {}x{} matrix",
+                self.columm_size, self.row_size, other.columm_size, other.row_size
+            );
+        }
+        HMatrix {
+            data: zip(&self.data, &other.data).map(|(a, b)| (*a).into() + (*b).into()).collect(),
+            row_size: self.row_size,
+            columm_size: self.columm_size,
+        }
+    }
+}
+
+impl<T, U> Add<HMatrix<U>> for HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    U: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn add(self, other: HMatrix<U>) -> HMatrix<f64> {
+        &self + &other
+    }
+}
+
+impl<T, U> Sub<&HMatrix<U>> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    U: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    /// Subtracts the second matrix from the first element-wise. Panics if the matrices do not have the same shape.
+    fn sub(self, other: &HMatrix<U>) -> HMatrix<f64> {
+        if self.shape() != other.shape() {
+            panic!(
+                "from: HMatrix Sub, cannot subtract a {}x{} matrix from a {}x{} matrix",
+                other.columm_size, other.row_size, self.columm_size, self.row_size
+            );
+        }
+        HMatrix {
+            data: zip(&self.data, &other.data).map(|(a, b)| (*a).into() - (*b).into()).collect(),
+            row_size: self.row_size,
+            columm_size: self.columm_size,
+        }
+    }
+}
+
+impl<T, U> Sub<HMatrix<U>> for HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    U: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn sub(self, other: HMatrix<U>) -> HMatrix<f64> {
+        &self - &other
+    }
+}
+
+impl<T, U> Mul<&HMatrix<U>> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    U: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    /// Multiplies two matrices, `self * other`. The number of columns of `self` must equal
+    /// the number of rows of `other`, otherwise this panics. An (m x n) * (n x p) product is an m x p matrix.
+    fn mul(self, other: &HMatrix<U>) -> HMatrix<f64> {
+        if self.row_size != other.columm_size {
+            panic!(
+                "from: HMatrix Mul, cannot multiply a {}x{} matrix by a {}x{} matrix, the inner dimensions must match",
+                self.columm_size, self.row_size, other.columm_size, other.row_size
+            );
+        }
+        let a: Vec<f64> = self.data.iter().map(|x| (*x).into()).collect();
+        let b: Vec<f64> = other.data.iter().map(|x| (*x).into()).collect();
+        let data: Vec<f64> = multiply_flat(&a, &b, self.columm_size, self.row_size, other.row_size);
+        HMatrix {
+            data,
+            row_size: other.row_size,
+            columm_size: self.columm_size,
+        }
+    }
+}
+
+impl<T, U> Mul<HMatrix<U>> for HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    U: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn mul(self, other: HMatrix<U>) -> HMatrix<f64> {
+        &self * &other
+    }
+}
+
+impl<T, S> Mul<&[S]> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    S: Copy + Into<f64>,
+{
+    type Output = Vec<f64>;
+
+    /// Multiplies the matrix by a column vector, the same as `vector.h_linear_transform(matrix)`.
+    /// Panics if the vector length does not equal the number of columns.
+    fn mul(self, vector: &[S]) -> Vec<f64> {
+        match vector.h_linear_transform(self) {
+            Some(result) => result,
+            None => panic!(
+                "from: HMatrix Mul, cannot multiply a {}x{} matrix by a vector of length {}",
+                self.columm_size, self.row_size, vector.len()
+            ),
+        }
+    }
+}
+
+impl<T, S> Mul<&Vec<S>> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+    S: Copy + Into<f64>,
+{
+    type Output = Vec<f64>;
+
+    fn mul(self, vector: &Vec<S>) -> Vec<f64> {
+        self * vector.as_slice()
+    }
+}
+
+impl<T> Mul<&HVector> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HVector;
+
+    /// Multiplies the matrix by an `HVector`. Panics if the vector length does not equal the number of columns.
+    fn mul(self, vector: &HVector) -> HVector {
+        HVector::from(self * &vector[..])
+    }
+}
+
+impl<T> Mul<f64> for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    /// Multiplies every element of the matrix by a scalar.
+    fn mul(self, scalar: f64) -> HMatrix<f64> {
+        HMatrix {
+            data: self.data.h_vector_scalar_mult(scalar),
+            row_size: self.row_size,
+            columm_size: self.columm_size,
+        }
+    }
+}
+
+impl<T> Mul<f64> for HMatrix<T>
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn mul(self, scalar: f64) -> HMatrix<f64> {
+        &self * scalar
+    }
+}
+
+impl<T> Mul<&HMatrix<T>> for f64
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn mul(self, matrix: &HMatrix<T>) -> HMatrix<f64> {
+        matrix * self
+    }
+}
+
+impl<T> Mul<HMatrix<T>> for f64
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn mul(self, matrix: HMatrix<T>) -> HMatrix<f64> {
+        &matrix * self
+    }
+}
+
+impl<T> Neg for &HMatrix<T>
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    /// Negates every element of the matrix.
+    fn neg(self) -> HMatrix<f64> {
+        self * -1.0
+    }
+}
+
+impl<T> Neg for HMatrix<T>
+where
+    T: Copy + Into<f64>,
+{
+    type Output = HMatrix<f64>;
+
+    fn neg(self) -> HMatrix<f64> {
+        &self * -1.0
+    }
+}
+
+// Matrices with complex entries. The generic operators above convert the elements to `f64`, which `HComplex`
+// cannot do, so complex matrices have their own operators that keep the result complex.
+
+impl HMatrix<HComplex> {
+    /// Returns the matrix of real parts.
+    pub fn h_real_part(&self) -> HMatrix<f64> {
+        self.map(|z| z.re())
+    }
+
+    /// Returns the matrix of imaginary parts.
+    pub fn h_imaginary_part(&self) -> HMatrix<f64> {
+        self.map(|z| z.im())
+    }
+
+    /// Returns the conjugate transpose (Hermitian adjoint) `Aᴴ`, the transpose with every element conjugated.
+    /// For complex matrices it takes the role the transpose has for real matrices, for example `Aᴴ = A` for Hermitian matrices.
+    pub fn h_conjugate_transpose(&self) -> HMatrix<HComplex> {
+        self.h_transpose().map(|z| z.h_conjugate())
+    }
+}
+
+impl Add<&HMatrix<HComplex>> for &HMatrix<HComplex> {
+    type Output = HMatrix<HComplex>;
+
+    /// Adds two complex matrices element-wise. Panics if the matrices do not have the same shape.
+    fn add(self, other: &HMatrix<HComplex>) -> HMatrix<HComplex> {
+        if self.shape() != other.shape() {
+            panic!(
+                "from: HMatrix Add, cannot add a {}x{} matrix and a {}x{} matrix",
+                self.columm_size, self.row_size, other.columm_size, other.row_size
+            );
+        }
+        HMatrix {
+            data: zip(&self.data, &other.data).map(|(a, b)| *a + *b).collect(),
+            row_size: self.row_size,
+            columm_size: self.columm_size,
+        }
+    }
+}
+
+impl Sub<&HMatrix<HComplex>> for &HMatrix<HComplex> {
+    type Output = HMatrix<HComplex>;
+
+    /// Subtracts two complex matrices element-wise. Panics if the matrices do not have the same shape.
+    fn sub(self, other: &HMatrix<HComplex>) -> HMatrix<HComplex> {
+        if self.shape() != other.shape() {
+            panic!(
+                "from: HMatrix Sub, cannot subtract a {}x{} matrix from a {}x{} matrix",
+                other.columm_size, other.row_size, self.columm_size, self.row_size
+            );
+        }
+        HMatrix {
+            data: zip(&self.data, &other.data).map(|(a, b)| *a - *b).collect(),
+            row_size: self.row_size,
+            columm_size: self.columm_size,
+        }
+    }
+}
+
+impl Mul<&HMatrix<HComplex>> for &HMatrix<HComplex> {
+    type Output = HMatrix<HComplex>;
+
+    /// Multiplies two complex matrices. Panics if the number of columns of `self` does not equal the number of rows of `other`.
+    fn mul(self, other: &HMatrix<HComplex>) -> HMatrix<HComplex> {
+        if self.row_size != other.columm_size {
+            panic!(
+                "from: HMatrix Mul, cannot multiply a {}x{} matrix by a {}x{} matrix",
+                self.columm_size, self.row_size, other.columm_size, other.row_size
+            );
+        }
+        HMatrix::from_fn(self.columm_size, other.row_size, |r, c| {
+            zip(self.row(r).unwrap(), other.col(c).unwrap()).map(|(a, b)| *a * *b).sum()
+        })
+    }
+}
+
+impl Mul<HComplex> for &HMatrix<HComplex> {
+    type Output = HMatrix<HComplex>;
+
+    fn mul(self, scalar: HComplex) -> HMatrix<HComplex> {
+        self.map(|z| z * scalar)
+    }
+}
+
+impl<T> Index<(usize, usize)> for HMatrix<T>
+where
+    T: Copy,
+{
+    type Output = T;
+
+    /// Returns the element at `(row, col)`. Panics if the indices are out of bounds, use `get` for a checked lookup.
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        if row >= self.columm_size || col >= self.row_size {
+            panic!(
+                "from: HMatrix Index, index ({}, {}) is out of bounds for a {}x{} matrix",
+                row, col, self.columm_size, self.row_size
+            );
+        }
+        &self.data[row * self.row_size + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for HMatrix<T>
+where
+    T: Copy,
+{
+    /// Returns a mutable reference to the element at `(row, col)`. Panics if the indices are out of bounds.
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        if row >= self.columm_size || col >= self.row_size {
+            panic!(
+                "from: HMatrix IndexMut, index ({}, {}) is out of bounds for a {}x{} matrix",
+                row, col, self.columm_size, self.row_size
+            );
+        }
+        &mut self.data[row * self.row_size + col]
+    }
+}
+
+
+/// Creates an `n x n` identity matrix of `f64`, for the routines whose element type is not known to implement `From<bool>`.
+fn identity_f64(n: usize) -> HMatrix<f64> {
+    HMatrix::from_fn(n, n, |r, c| if r == c { 1.0 } else { 0.0 })
+}
+
+/// Returns the tolerance below which a pivot is treated as zero during elimination.
+/// The tolerance scales with the size of the matrix and the magnitude of its largest entry,
+/// so that the same matrix multiplied by a constant keeps the same rank and invertibility.
+pub(crate) fn elimination_tolerance(data: &[f64], rows: usize, cols: usize) -> f64 {
+    let max_abs: f64 = data.iter().fold(0.0, |acc: f64, x| acc.max(x.abs()));
+    rows.max(cols) as f64 * f64::EPSILON * max_abs
+}
+
+/// Finds the row in `start_row..end_row` with the largest absolute value in column `col`
+/// of a flat row-major matrix with `width` columns.
+fn partial_pivot(a: &[f64], width: usize, col: usize, start_row: usize, end_row: usize) -> usize {
+    let mut best: usize = start_row;
+    for r in start_row + 1..end_row {
+        if a[r * width + col].abs() > a[best * width + col].abs() {
+            best = r;
+        }
+    }
+    best
+}
+
+/// Swaps two rows of a flat row-major matrix with `width` columns.
+fn swap_rows_flat(a: &mut [f64], width: usize, row1: usize, row2: usize) {
+    if row1 == row2 {
+        return;
+    }
+    for c in 0..width {
+        a.swap(row1 * width + c, row2 * width + c);
+    }
+}
+
+
+/// The side length of the square tiles used by `multiply_flat`. A 64 x 64 tile of `f64` is 32 KiB,
+/// so a tile of `B` and the matching row strips of `A` and `C` stay in the L1/L2 cache while they are reused.
+const MULTIPLY_BLOCK_SIZE: usize = 64;
+
+/// Below this many multiply-adds (m * n * p), spawning threads costs more than it saves.
+#[cfg(feature = "parallel")]
+const PARALLEL_MULTIPLY_THRESHOLD: usize = 64 * 64 * 64;
+
+/// Multiplies the flat row-major m x n matrix `a` by the n x p matrix `b`, returning the m x p product.
+/// With the `parallel` feature, large products are split into bands of rows that are computed on separate threads.
+fn multiply_flat(a: &[f64], b: &[f64], m: usize, n: usize, p: usize) -> Vec<f64> {
+    let mut c: Vec<f64> = vec![0.0; m * p];
+    if m == 0 || n == 0 || p == 0 {
+        return c;
+    }
+
+    #[cfg(feature = "parallel")]
+    {
+        let threads: usize = std::thread::available_parallelism().map_or(1, |t| t.get()).min(m);
+        if threads > 1 && m * n * p >= PARALLEL_MULTIPLY_THRESHOLD {
+            let rows_per_thread: usize = m.div_ceil(threads);
+            std::thread::scope(|scope| {
+                for (a_band, c_band) in zip(a.chunks(rows_per_thread * n), c.chunks_mut(rows_per_thread * p)) {
+                    scope.spawn(move || multiply_rows(a_band, b, n, p, c_band));
+                }
+            });
+            return c;
+        }
+    }
+
+    multiply_rows(a, b, n, p, &mut c);
+    c
+}
+
+/// Adds `a * b` to `c` for a band of rows of `a` (row-major, n columns) and `c` (row-major, p columns),
+/// looping over square tiles so that every loaded tile of `b` is used for a whole block of rows.
+/// The innermost loop runs along contiguous rows of `b` and `c`, which the compiler can vectorize.
+fn multiply_rows(a: &[f64], b: &[f64], n: usize, p: usize, c: &mut [f64]) {
+    let rows: usize = c.len() / p;
+    for row_start in (0..rows).step_by(MULTIPLY_BLOCK_SIZE) {
+        let row_end: usize = (row_start + MULTIPLY_BLOCK_SIZE).min(rows);
+        for k_start in (0..n).step_by(MULTIPLY_BLOCK_SIZE) {
+            let k_end: usize = (k_start + MULTIPLY_BLOCK_SIZE).min(n);
+            for col_start in (0..p).step_by(MULTIPLY_BLOCK_SIZE) {
+                let col_end: usize = (col_start + MULTIPLY_BLOCK_SIZE).min(p);
+                for i in row_start..row_end {
+                    let c_row: &mut [f64] = &mut c[i * p + col_start..i * p + col_end];
+                    for k in k_start..k_end {
+                        let a_ik: f64 = a[i * n + k];
+                        let b_row: &[f64] = &b[k * p + col_start..k * p + col_end];
+                        for (c_ij, b_kj) in c_row.iter_mut().zip(b_row) {
+                            *c_ij += a_ik * b_kj;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether a flat row-major `n x n` matrix is symmetric, up to the given tolerance.
+fn is_symmetric_flat(a: &[f64], n: usize, tolerance: f64) -> bool {
+    for r in 0..n {
+        for c in r + 1..n {
+            if (a[r * n + c] - a[c * n + r]).abs() > tolerance {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+
+/// The LU decomposition `PA = LU` of a square matrix, created by `HMatrix::h_lu`.
+/// `L` and `U` are stored together in one matrix: the strictly lower part holds `L`
+/// (whose diagonal is all ones) and the upper part including the diagonal holds `U`.
+/// The row permutation is stored as a list of row indices, so that row `i` of `PA` is row `permutation[i]` of `A`.
+pub struct HLuDecomposition {
+    lu: HMatrix<f64>,
+    permutation: Vec<usize>,
+    swaps: usize,
+    tolerance: f64,
+}
+
+impl HLuDecomposition {
+    /// Returns the unit lower triangular factor `L`.
+    pub fn l(&self) -> HMatrix<f64> {
+        let n: usize = self.lu.row_size;
+        let mut data: Vec<f64> = vec![0.0; n * n];
+        for r in 0..n {
+            data[r * n..r * n + r].copy_from_slice(&self.lu.data[r * n..r * n + r]);
+            data[r * n + r] = 1.0;
+        }
+        HMatrix {
+            data,
+            row_size: n,
+            columm_size: n,
+        }
+    }
+
+    /// Returns the upper triangular factor `U`.
+    pub fn u(&self) -> HMatrix<f64> {
+        let n: usize = self.lu.row_size;
+        let mut data: Vec<f64> = vec![0.0; n * n];
+        for r in 0..n {
+            data[r * n + r..(r + 1) * n].copy_from_slice(&self.lu.data[r * n + r..(r + 1) * n]);
+        }
+        HMatrix {
+            data,
+            row_size: n,
+            columm_size: n,
+        }
+    }
+
+    /// Returns the permutation matrix `P`.
+    pub fn p(&self) -> HMatrix<f64> {
+        let n: usize = self.lu.row_size;
+        let mut data: Vec<f64> = vec![0.0; n * n];
+        for (r, original) in self.permutation.iter().enumerate() {
+            data[r * n + original] = 1.0;
+        }
+        HMatrix {
+            data,
+            row_size: n,
+            columm_size: n,
+        }
+    }
+
+    /// Returns the row permutation, where row `i` of `PA` is row `permutation()[i]` of `A`.
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Returns `true` if one of the pivots of `U` is numerically zero.
+    pub fn is_singular(&self) -> bool {
+        let n: usize = self.lu.row_size;
+        (0..n).any(|i| self.lu.data[i * n + i].abs() <= self.tolerance)
+    }
+
+    /// Calculates the determinant of the decomposed matrix as the product of the pivots of `U`,
+    /// negated if an odd number of row swaps was made. Returns `0.0` for singular matrices.
+    pub fn h_determinant(&self) -> f64 {
+        if self.is_singular() {
+            return 0.0;
+        }
+        let n: usize = self.lu.row_size;
+        let product: f64 = (0..n).map(|i| self.lu.data[i * n + i]).product();
+        if self.swaps.is_multiple_of(2) { product } else { -product }
+    }
+
+    /// Solves `Ax = b` using forward substitution with `L` and back substitution with `U`.
+    /// Returns `LinearSystemError::DimensionMismatch` if `rhs` has the wrong length,
+    /// and `LinearSystemError::Singular` if the decomposed matrix is singular.
+    pub fn h_solve<S>(&self, rhs: &[S]) -> Result<Vec<f64>, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        let n: usize = self.lu.row_size;
+        if rhs.len() != n {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: n,
+                rhs_len: rhs.len(),
+            });
+        }
+        if self.is_singular() {
+            return Err(LinearSystemError::Singular);
+        }
+        let a: &[f64] = &self.lu.data;
+
+        let mut y: Vec<f64> = vec![0.0; n];
+        for i in 0..n {
+            y[i] = rhs[self.permutation[i]].into() - h_dot(&a[i * n..i * n + i], &y[..i]);
+        }
+        let mut x: Vec<f64> = vec![0.0; n];
+        for i in (0..n).rev() {
+            x[i] = (y[i] - h_dot(&a[i * n + i + 1..(i + 1) * n], &x[i + 1..])) / a[i * n + i];
+        }
+        Ok(x)
+    }
+
+    /// Calculates the inverse of the decomposed matrix by solving for every column of the identity matrix.
+    /// Returns `None` if the matrix is singular.
+    pub fn h_inverse(&self) -> Option<HMatrix<f64>> {
+        let n: usize = self.lu.row_size;
+        let mut data: Vec<f64> = vec![0.0; n * n];
+        let mut unit: Vec<f64> = vec![0.0; n];
+        for c in 0..n {
+            unit[c] = 1.0;
+            let column: Vec<f64> = self.h_solve(&unit).ok()?;
+            unit[c] = 0.0;
+            for r in 0..n {
+                data[r * n + c] = column[r];
+            }
+        }
+        Some(HMatrix {
+            data,
+            row_size: n,
+            columm_size: n,
+        })
+    }
+}
+
+
+/// The QR decomposition `A = QR` of a matrix, created by `HMatrix::h_qr`.
+/// `Q` is an orthogonal m x m matrix and `R` is an upper triangular m x n matrix.
+pub struct HQrDecomposition {
+    q: HMatrix<f64>,
+    r: HMatrix<f64>,
+    tolerance: f64,
+}
+
+impl HQrDecomposition {
+    /// Returns the orthogonal factor `Q`.
+    pub fn q(&self) -> &HMatrix<f64> {
+        &self.q
+    }
+
+    /// Returns the upper triangular factor `R`.
+    pub fn r(&self) -> &HMatrix<f64> {
+        &self.r
+    }
+
+    /// Solves `Ax = b` in the least-squares sense, by computing `Qᵀb` and back substituting with `R`.
+    /// For a square matrix this is the exact solution. For a matrix with more rows than columns
+    /// it is the `x` that minimizes `||Ax - b||`.
+    /// Returns `LinearSystemError::DimensionMismatch` if `rhs` has the wrong length, and
+    /// `LinearSystemError::Underdetermined` (or `Singular` for square matrices) if the columns are linearly dependent.
+    pub fn h_solve<S>(&self, rhs: &[S]) -> Result<Vec<f64>, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        let m: usize = self.r.columm_size;
+        let n: usize = self.r.row_size;
+        if rhs.len() != m {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: m,
+                rhs_len: rhs.len(),
+            });
+        }
+        let r: &[f64] = &self.r.data;
+        let rank: usize = (0..n.min(m)).filter(|&i| r[i * n + i].abs() > self.tolerance).count();
+        if rank < n {
+            if m == n {
+                return Err(LinearSystemError::Singular);
+            }
+            return Err(LinearSystemError::Underdetermined { rank, unknowns: n });
+        }
+
+        let b: Vec<f64> = rhs.iter().map(|x| (*x).into()).collect();
+        let q_transpose_b: Vec<f64> = (0..n)
+            .map(|i| h_dot(&self.q.get_col(i).unwrap(), &b))
+            .collect();
+        let mut x: Vec<f64> = vec![0.0; n];
+        for i in (0..n).rev() {
+            x[i] = (q_transpose_b[i] - h_dot(&r[i * n + i + 1..(i + 1) * n], &x[i + 1..])) / r[i * n + i];
+        }
+        Ok(x)
+    }
+}
+
+
+/// The result of a least-squares fit `y ≈ Ax`, created by `HMatrix::h_least_squares`.
+pub struct HLeastSquares {
+    coefficients: Vec<f64>,
+    fitted_values: Vec<f64>,
+    residuals: Vec<f64>,
+    residual_sum_of_squares: f64,
+    r_squared: f64,
+}
+
+impl HLeastSquares {
+    /// Returns the fitted coefficients `x`, one per column of the design matrix.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Returns the fitted values `Ax`, one per observation.
+    pub fn fitted_values(&self) -> &[f64] {
+        &self.fitted_values
+    }
+
+    /// Returns the residuals `y - Ax`, one per observation.
+    pub fn residuals(&self) -> &[f64] {
+        &self.residuals
+    }
+
+    /// Returns the residual sum of squares `||y - Ax||²`, the quantity the fit minimizes.
+    pub fn residual_sum_of_squares(&self) -> f64 {
+        self.residual_sum_of_squares
+    }
+
+    /// Returns the coefficient of determination `R² = 1 - SS_res / SS_tot`, the fraction of the variance
+    /// of the observations that the model explains. It is 1 for a perfect fit and is only guaranteed
+    /// to lie in `[0, 1]` when the design matrix contains an intercept column.
+    pub fn r_squared(&self) -> f64 {
+        self.r_squared
+    }
+
+    /// Predicts the value of the model for a new row of the design matrix.
+    /// If the row does not have one value per coefficient, the function returns `None`.
+    pub fn h_predict<S>(&self, row: &[S]) -> Option<f64>
+    where
+        S: Copy + Into<f64>,
+    {
+        if row.len() != self.coefficients.len() {
+            return None;
+        }
+        Some(h_dot(row, &self.coefficients))
+    }
+}
+
+
+/// The Cholesky decomposition `A = LLᵀ` of a symmetric positive-definite matrix, created by `HMatrix::h_cholesky`.
+pub struct HCholeskyDecomposition {
+    l: HMatrix<f64>,
+}
+
+impl HCholeskyDecomposition {
+    /// Returns the lower triangular factor `L`.
+    pub fn l(&self) -> &HMatrix<f64> {
+        &self.l
+    }
+
+    /// Calculates the determinant of the decomposed matrix, which is the square of the product of the diagonal of `L`.
+    pub fn h_determinant(&self) -> f64 {
+        let n: usize = self.l.row_size;
+        let product: f64 = (0..n).map(|i| self.l.data[i * n + i]).product();
+        product * product
+    }
+
+    /// Solves `Ax = b` using forward substitution with `L` and back substitution with `Lᵀ`.
+    /// Returns `LinearSystemError::DimensionMismatch` if `rhs` has the wrong length.
+    pub fn h_solve<S>(&self, rhs: &[S]) -> Result<Vec<f64>, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        let n: usize = self.l.row_size;
+        if rhs.len() != n {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: n,
+                rhs_len: rhs.len(),
+            });
+        }
+        let l: &[f64] = &self.l.data;
+
+        let mut y: Vec<f64> = vec![0.0; n];
+        for i in 0..n {
+            y[i] = (rhs[i].into() - h_dot(&l[i * n..i * n + i], &y[..i])) / l[i * n + i];
+        }
+        let mut x: Vec<f64> = vec![0.0; n];
+        for i in (0..n).rev() {
+            let sum: f64 = (i + 1..n).map(|k| l[k * n + i] * x[k]).sum();
+            x[i] = (y[i] - sum) / l[i * n + i];
+        }
+        Ok(x)
+    }
+}
+
+
+/// The eigen-decomposition `A = VΛVᵀ` of a symmetric matrix, created by `HMatrix::h_symmetric_eigen`.
+/// The eigenvalues are sorted in descending order, and column `i` of the eigenvector matrix
+/// is the unit eigenvector belonging to eigenvalue `i`.
+pub struct HEigenDecomposition {
+    eigenvalues: Vec<f64>,
+    eigenvectors: HMatrix<f64>,
+}
+
+impl HEigenDecomposition {
+    /// Returns the eigenvalues in descending order.
+    pub fn eigenvalues(&self) -> &[f64] {
+        &self.eigenvalues
+    }
+
+    /// Returns the eigenvectors as the columns of an orthogonal matrix.
+    pub fn eigenvectors(&self) -> &HMatrix<f64> {
+        &self.eigenvectors
+    }
+}
+
+
+/// An eigenvalue of a real matrix, as returned by `HMatrix::h_eigenvalues`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HEigenvalue {
+    /// A real eigenvalue.
+    Real(f64),
+    /// A pair of complex conjugate eigenvalues `re + im·i` and `re - im·i`, where `im` is always positive.
+    ComplexPair { re: f64, im: f64 },
+}
+
+impl HEigenvalue {
+    /// Returns the real and (non-negative) imaginary part of the eigenvalue.
+    fn parts(&self) -> (f64, f64) {
+        match self {
+            HEigenvalue::Real(re) => (*re, 0.0),
+            HEigenvalue::ComplexPair { re, im } => (*re, *im),
+        }
+    }
+}
+
+
+/// The thin singular value decomposition `A = UΣVᵀ` of a matrix, created by `HMatrix::h_svd`.
+pub struct HSvd {
+    u: HMatrix<f64>,
+    singular_values: Vec<f64>,
+    vt: HMatrix<f64>,
+}
+
+impl HSvd {
+    /// Returns the left singular vectors as the columns of `U`.
+    pub fn u(&self) -> &HMatrix<f64> {
+        &self.u
+    }
+
+    /// Returns the singular values in descending order, the diagonal of `Σ`.
+    pub fn singular_values(&self) -> &[f64] {
+        &self.singular_values
+    }
+
+    /// Returns `Σ` as a square diagonal matrix.
+    pub fn sigma(&self) -> HMatrix<f64> {
+        let k: usize = self.singular_values.len();
+        let mut data: Vec<f64> = vec![0.0; k * k];
+        for (i, sigma) in self.singular_values.iter().enumerate() {
+            data[i * k + i] = *sigma;
+        }
+        HMatrix {
+            data,
+            row_size: k,
+            columm_size: k,
+        }
+    }
+
+    /// Returns the right singular vectors as the rows of `Vᵀ`.
+    pub fn vt(&self) -> &HMatrix<f64> {
+        &self.vt
+    }
+
+    /// Returns the default tolerance below which a singular value is treated as zero.
+    fn default_tolerance(&self) -> f64 {
+        self.u.columm_size.max(self.vt.row_size) as f64 * f64::EPSILON * self.singular_values[0]
+    }
+
+    /// Calculates the number of singular values above `tolerance`, or above the default
+    /// `max(m, n) * ε * σ_max` if `tolerance` is `None`.
+    pub fn h_rank(&self, tolerance: Option<f64>) -> usize {
+        let tolerance: f64 = tolerance.unwrap_or_else(|| self.default_tolerance());
+        self.singular_values.iter().filter(|sigma| **sigma > tolerance).count()
+    }
+
+    /// Calculates the ratio between the largest and smallest singular value.
+    /// Returns `f64::INFINITY` if the smallest singular value is zero.
+    pub fn h_condition_number(&self) -> f64 {
+        let smallest: f64 = self.singular_values[self.singular_values.len() - 1];
+        if smallest == 0.0 {
+            return f64::INFINITY;
+        }
+        self.singular_values[0] / smallest
+    }
+
+    /// Calculates the Moore-Penrose pseudo-inverse `VΣ⁺Uᵀ`, treating singular values below the default tolerance as zero.
+    pub fn h_pseudo_inverse(&self) -> HMatrix<f64> {
+        let m: usize = self.u.columm_size;
+        let n: usize = self.vt.row_size;
+        let k: usize = self.singular_values.len();
+        let tolerance: f64 = self.default_tolerance();
+
+        let mut data: Vec<f64> = vec![0.0; n * m];
+        for (s, sigma) in self.singular_values.iter().enumerate() {
+            if *sigma <= tolerance {
+                continue;
+            }
+            for i in 0..n {
+                let v_is: f64 = self.vt.data[s * n + i] / sigma;
+                for j in 0..m {
+                    data[i * m + j] += v_is * self.u.data[j * k + s];
+                }
+            }
+        }
+        HMatrix {
+            data,
+            row_size: m,
+            columm_size: n,
+        }
+    }
+}
+
+
+/// Applies a plane rotation to columns `p` and `q` of a matrix stored as a list of columns.
+fn rotate_columns(cols: &mut [Vec<f64>], p: usize, q: usize, c: f64, s: f64) {
+    for i in 0..cols[p].len() {
+        let col_p: f64 = cols[p][i];
+        let col_q: f64 = cols[q][i];
+        cols[p][i] = c * col_p - s * col_q;
+        cols[q][i] = s * col_p + c * col_q;
+    }
+}
+
+/// Orthonormalizes `vectors` in order with the modified Gram-Schmidt process: each accepted basis vector is
+/// immediately projected out of all the remaining vectors, which keeps the basis orthogonal in floating point
+/// far better than subtracting every projection from the original vector (classical Gram-Schmidt).
+/// A vector whose remaining component is below `sqrt(ε)` times its original magnitude is treated as
+/// linearly dependent on the earlier vectors and skipped.
+fn modified_gram_schmidt(mut vectors: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let norms: Vec<f64> = vectors.iter().map(|v| v.h_magnitude()).collect();
+    let mut basis: Vec<Vec<f64>> = Vec::new();
+    for i in 0..vectors.len() {
+        let norm: f64 = vectors[i].h_magnitude();
+        if norm == 0.0 || norm <= f64::EPSILON.sqrt() * norms[i] {
+            continue;
+        }
+        let q: Vec<f64> = vectors[i].h_vector_scalar_div(norm);
+        for vector in vectors.iter_mut().skip(i + 1) {
+            let projection: f64 = h_dot(&q, vector);
+            *vector = h_vector_sub(vector, &q.h_vector_scalar_mult(projection));
+        }
+        basis.push(q);
+    }
+    basis
+}
+
+/// Completes the orthonormal `basis` of a subspace of ℝ^len with an orthonormal basis of its orthogonal complement,
+/// and returns the complement. This is modified Gram-Schmidt on the standard basis vectors with pivoting:
+/// at each step the candidate with the largest remaining component is accepted, so it never divides by a tiny norm.
+fn orthogonal_complement(basis: Vec<Vec<f64>>, len: usize) -> Vec<Vec<f64>> {
+    let mut candidates: Vec<Vec<f64>> = (0..len)
+        .map(|i| (0..len).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for q in &basis {
+        for candidate in candidates.iter_mut() {
+            let projection: f64 = h_dot(q, candidate);
+            *candidate = h_vector_sub(candidate, &q.h_vector_scalar_mult(projection));
+        }
+    }
+
+    let mut complement: Vec<Vec<f64>> = Vec::new();
+    while basis.len() + complement.len() < len {
+        let best: usize = (0..candidates.len())
+            .max_by(|a, b| candidates[*a].h_magnitude().total_cmp(&candidates[*b].h_magnitude()))
+            .unwrap();
+        let chosen: Vec<f64> = candidates.swap_remove(best);
+        let q: Vec<f64> = chosen.h_vector_scalar_div(chosen.h_magnitude());
+        for candidate in candidates.iter_mut() {
+            let projection: f64 = h_dot(&q, candidate);
+            *candidate = h_vector_sub(candidate, &q.h_vector_scalar_mult(projection));
+        }
+        complement.push(q);
+    }
+    complement
+}
+
+/// Builds a `len x basis.len()` matrix with the vectors of `basis` as its columns, each of length `len`.
+/// An empty basis keeps its `len` rows and gives a `len x 0` matrix, so the shape still says which space it spans.
+fn matrix_from_basis(basis: Vec<Vec<f64>>, len: usize) -> HMatrix<f64> {
+    HMatrix::from_fn(len, basis.len(), |r, c| basis[c][r])
+}
+
+/// Returns a unit vector of length `len` that is orthogonal to all of `basis`, which must be orthonormal
+/// and have fewer than `len` vectors. The standard basis vectors are tried in turn, and the first one
+/// that keeps a clear component after removing its projection onto `basis` is used.
+fn orthonormal_complement(basis: &[Vec<f64>], len: usize) -> Vec<f64> {
+    for i in 0..len {
+        let mut candidate: Vec<f64> = vec![0.0; len];
+        candidate[i] = 1.0;
+        for vector in basis {
+            let projection: f64 = h_dot(vector, &candidate);
+            candidate = h_vector_sub(&candidate, &vector.h_vector_scalar_mult(projection));
+        }
+        let norm: f64 = candidate.h_magnitude();
+        if norm > 0.5 {
+            return candidate.h_vector_scalar_div(norm);
+        }
+    }
+    vec![0.0; len]
+}
+
+
+/// Reduces a flat row-major `n x n` matrix to upper Hessenberg form in place, using Gaussian elimination
+/// with pivoting applied as similarity transforms, so the eigenvalues are unchanged.
+/// The entries below the subdiagonal are set to zero.
+fn hessenberg_reduce(a: &mut [f64], n: usize) {
+    for m in 1..n.saturating_sub(1) {
+        let mut x: f64 = 0.0;
+        let mut i: usize = m;
+        for j in m..n {
+            if a[j * n + m - 1].abs() > x.abs() {
+                x = a[j * n + m - 1];
+                i = j;
+            }
+        }
+        if i != m {
+            for j in m - 1..n {
+                a.swap(i * n + j, m * n + j);
+            }
+            for j in 0..n {
+                a.swap(j * n + i, j * n + m);
+            }
+        }
+        if x != 0.0 {
+            for i in m + 1..n {
+                let y: f64 = a[i * n + m - 1] / x;
+                if y == 0.0 {
+                    continue;
+                }
+                a[i * n + m - 1] = 0.0;
+                for j in m..n {
+                    a[i * n + j] -= y * a[m * n + j];
+                }
+                for j in 0..n {
+                    a[j * n + m] += y * a[j * n + i];
+                }
+            }
+        }
+    }
+}
+
+/// Finds all eigenvalues of an upper Hessenberg matrix with the Francis double-shift QR algorithm,
+/// returning the real and imaginary parts. The matrix is destroyed in the process.
+/// Returns `None` if an eigenvalue does not converge within 30 iterations.
+fn hessenberg_qr(h: &mut [f64], n: usize) -> Option<(Vec<f64>, Vec<f64>)> {
+    // The algorithm is written with 1-based indices, which keeps the many `k - 1` and `k + 2` offsets readable.
+    let idx = |i: usize, j: usize| (i - 1) * n + (j - 1);
+    let mut real: Vec<f64> = vec![0.0; n + 1];
+    let mut imaginary: Vec<f64> = vec![0.0; n + 1];
+
+    let mut norm: f64 = 0.0;
+    for i in 1..=n {
+        for j in i.saturating_sub(1).max(1)..=n {
+            norm += h[idx(i, j)].abs();
+        }
+    }
+
+    let mut nn: usize = n;
+    let mut t: f64 = 0.0;
+    while nn >= 1 {
+        let mut its: usize = 0;
+        loop {
+            // Look for a single small subdiagonal element to split the matrix.
+            let mut l: usize = nn;
+            while l >= 2 {
+                let mut s: f64 = h[idx(l - 1, l - 1)].abs() + h[idx(l, l)].abs();
+                if s == 0.0 {
+                    s = norm;
+                }
+                if h[idx(l, l - 1)].abs() + s == s {
+                    h[idx(l, l - 1)] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            let mut x: f64 = h[idx(nn, nn)];
+            if l == nn {
+                // One root found.
+                real[nn] = x + t;
+                imaginary[nn] = 0.0;
+                nn -= 1;
+            } else {
+                let mut y: f64 = h[idx(nn - 1, nn - 1)];
+                let mut w: f64 = h[idx(nn, nn - 1)] * h[idx(nn - 1, nn)];
+                if l == nn - 1 {
+                    // Two roots found.
+                    let p: f64 = 0.5 * (y - x);
+                    let q: f64 = p * p + w;
+                    let mut z: f64 = q.abs().sqrt();
+                    x += t;
+                    if q >= 0.0 {
+                        z = p + z.copysign(p);
+                        real[nn - 1] = x + z;
+                        real[nn] = x + z;
+                        if z != 0.0 {
+                            real[nn] = x - w / z;
+                        }
+                        imaginary[nn - 1] = 0.0;
+                        imaginary[nn] = 0.0;
+                    } else {
+                        real[nn - 1] = x + p;
+                        real[nn] = x + p;
+                        imaginary[nn - 1] = -z;
+                        imaginary[nn] = z;
+                    }
+                    nn -= 2;
+                } else {
+                    if its == 30 {
+                        return None;
+                    }
+                    if its == 10 || its == 20 {
+                        // Exceptional shift.
+                        t += x;
+                        for i in 1..=nn {
+                            h[idx(i, i)] -= x;
+                        }
+                        let s: f64 = h[idx(nn, nn - 1)].abs() + h[idx(nn - 1, nn - 2)].abs();
+                        x = 0.75 * s;
+                        y = x;
+                        w = -0.4375 * s * s;
+                    }
+                    its += 1;
+
+                    // Form the shift and look for two consecutive small subdiagonal elements.
+                    let mut m: usize = nn - 2;
+                    let (mut p, mut q, mut r): (f64, f64, f64);
+                    loop {
+                        let z: f64 = h[idx(m, m)];
+                        r = x - z;
+                        let s: f64 = y - z;
+                        p = (r * s - w) / h[idx(m + 1, m)] + h[idx(m, m + 1)];
+                        q = h[idx(m + 1, m + 1)] - z - r - s;
+                        r = h[idx(m + 2, m + 1)];
+                        let s: f64 = p.abs() + q.abs() + r.abs();
+                        p /= s;
+                        q /= s;
+                        r /= s;
+                        if m == l {
+                            break;
+                        }
+                        let u: f64 = h[idx(m, m - 1)].abs() * (q.abs() + r.abs());
+                        let v: f64 = p.abs() * (h[idx(m - 1, m - 1)].abs() + z.abs() + h[idx(m + 1, m + 1)].abs());
+                        if u + v == v {
+                            break;
+                        }
+                        m -= 1;
+                    }
+                    for i in m + 2..=nn {
+                        h[idx(i, i - 2)] = 0.0;
+                        if i != m + 2 {
+                            h[idx(i, i - 3)] = 0.0;
+                        }
+                    }
+
+                    // Double QR step on rows l..nn and columns m..nn.
+                    for k in m..nn {
+                        if k != m {
+                            p = h[idx(k, k - 1)];
+                            q = h[idx(k + 1, k - 1)];
+                            r = 0.0;
+                            if k != nn - 1 {
+                                r = h[idx(k + 2, k - 1)];
+                            }
+                            x = p.abs() + q.abs() + r.abs();
+                            if x != 0.0 {
+                                p /= x;
+                                q /= x;
+                                r /= x;
+                            }
+                        }
+                        let s: f64 = (p * p + q * q + r * r).sqrt().copysign(p);
+                        if s == 0.0 {
+                            continue;
+                        }
+                        if k == m {
+                            if l != m {
+                                h[idx(k, k - 1)] = -h[idx(k, k - 1)];
+                            }
+                        } else {
+                            h[idx(k, k - 1)] = -s * x;
+                        }
+                        p += s;
+                        x = p / s;
+                        y = q / s;
+                        let z: f64 = r / s;
+                        q /= p;
+                        r /= p;
+                        for j in k..=nn {
+                            let mut p: f64 = h[idx(k, j)] + q * h[idx(k + 1, j)];
+                            if k != nn - 1 {
+                                p += r * h[idx(k + 2, j)];
+                                h[idx(k + 2, j)] -= p * z;
+                            }
+                            h[idx(k + 1, j)] -= p * y;
+                            h[idx(k, j)] -= p * x;
+                        }
+                        for i in l..=nn.min(k + 3) {
+                            let mut p: f64 = x * h[idx(i, k)] + y * h[idx(i, k + 1)];
+                            if k != nn - 1 {
+                                p += z * h[idx(i, k + 2)];
+                                h[idx(i, k + 2)] -= p * r;
+                            }
+                            h[idx(i, k + 1)] -= p * q;
+                            h[idx(i, k)] -= p;
+                        }
+                    }
+                }
+            }
+            if nn < 2 || l + 1 >= nn {
+                break;
+            }
+        }
+    }
+
+    real.remove(0);
+    imaginary.remove(0);
+    Some((real, imaginary))
+}
+
+
+/// Describes why a vector operation could not be performed, by `HVector`, `HVectorN` or `HQuaternion`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VectorError {
+    /// The two vectors do not have the same number of elements.
+    LengthMismatch { left: usize, right: usize },
+    /// The operation needs a direction, but the vector has zero magnitude.
+    ZeroVector,
+}
+
+
+/// A vector of `f64` elements with operator overloads and length-checked operations.
+/// The checked methods, such as `h_add` and `h_euclidean_distance`, return `VectorError::LengthMismatch`
+/// instead of panicking when the lengths differ, and the operators `+`, `-` and `*` panic with a message
+/// that includes both lengths. `HVector` dereferences to `[f64]`, so the slice functions and traits,
+/// such as `h_dot`, `h_magnitude` and `h_linear_transform`, keep working on it.
+///
+/// Example:
+///
+/// let a = HVector::new(&[1, 2, 3]);
+/// let b = HVector::new(&[4.0, 5.0, 6.0]);
+/// assert_eq!(&a + &b, HVector::new(&[5.0, 7.0, 9.0]));
+/// assert_eq!(a.h_dot(&[1, 0, 0]), Ok(1.0));
+/// assert!(a.h_add(&[1.0]).is_err());
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HVector {
+    data: Vec<f64>,
+}
+
+impl HVector {
+    /// Creates a vector from a slice of any type that can be converted into `f64`.
+    pub fn new<T>(elements: &[T]) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        HVector {
+            data: elements.iter().map(|x| (*x).into()).collect(),
+        }
+    }
+
+    /// Creates a vector of `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        HVector { data: vec![0.0; len] }
+    }
+
+    /// Consumes the vector and returns its elements.
+    pub fn into_vec(self) -> Vec<f64> {
+        self.data
+    }
+
+    /// Returns an error if `other` does not have the same length as the vector.
+    fn check_len(&self, other_len: usize) -> Result<(), VectorError> {
+        if self.data.len() != other_len {
+            return Err(VectorError::LengthMismatch {
+                left: self.data.len(),
+                right: other_len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies `f` to each pair of elements, after checking that the lengths match.
+    fn zip_with<S, F>(&self, other: &[S], f: F) -> Result<HVector, VectorError>
+    where
+        S: Copy + Into<f64>,
+        F: Fn(f64, f64) -> f64,
+    {
+        self.check_len(other.len())?;
+        Ok(HVector {
+            data: zip(&self.data, other).map(|(a, b)| f(*a, (*b).into())).collect(),
+        })
+    }
+
+    /// Adds another vector element-wise. Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_add<S>(&self, other: &[S]) -> Result<HVector, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        self.zip_with(other, |a, b| a + b)
+    }
+
+    /// Subtracts another vector element-wise. Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_sub<S>(&self, other: &[S]) -> Result<HVector, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        self.zip_with(other, |a, b| a - b)
+    }
+
+    /// Multiplies another vector element-wise (the Hadamard product).
+    /// Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_hadamard<S>(&self, other: &[S]) -> Result<HVector, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        self.zip_with(other, |a, b| a * b)
+    }
+
+    /// Calculates the dot product with another vector. Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_dot<S>(&self, other: &[S]) -> Result<f64, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        self.check_len(other.len())?;
+        Ok(h_dot(&self.data, other))
+    }
+
+    /// Returns the vector scaled to a magnitude of 1. Returns `VectorError::ZeroVector` if the vector is zero.
+    ///
+    /// Example:
+    ///
+    /// let v = HVector::new(&[3.0, 4.0]);
+    /// assert_eq!(v.h_normalize(), Ok(HVector::new(&[0.6, 0.8])));
+    pub fn h_normalize(&self) -> Result<HVector, VectorError> {
+        let magnitude: f64 = self.h_magnitude();
+        if magnitude == 0.0 {
+            return Err(VectorError::ZeroVector);
+        }
+        Ok(self / magnitude)
+    }
+
+    /// Calculates the Euclidean (L2) distance `sqrt(Σ(a[i] - b[i])^2)`, the straight-line distance between two points.
+    /// Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_euclidean_distance<S>(&self, other: &[S]) -> Result<f64, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        Ok(self.h_sub(other)?.h_magnitude())
+    }
+
+    /// Calculates the Manhattan (L1) distance `Σ|a[i] - b[i]|`, the distance travelled along the axes.
+    /// Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_manhattan_distance<S>(&self, other: &[S]) -> Result<f64, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        Ok(self.h_sub(other)?.iter().map(|x| x.abs()).sum())
+    }
+
+    /// Calculates the Chebyshev (L∞) distance `max|a[i] - b[i]|`, the largest difference along any axis.
+    /// Returns `VectorError::LengthMismatch` if the lengths differ.
+    pub fn h_chebyshev_distance<S>(&self, other: &[S]) -> Result<f64, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        Ok(self.h_sub(other)?.iter().fold(0.0, |acc: f64, x| acc.max(x.abs())))
+    }
+
+    /// Calculates the cosine distance `1 - (a · b) / (||a|| ||b||)`, which is 0 for vectors pointing the same way,
+    /// 1 for perpendicular vectors and 2 for opposite vectors, regardless of their magnitudes.
+    /// Returns `VectorError::LengthMismatch` if the lengths differ, and `VectorError::ZeroVector` if either vector is zero.
+    ///
+    /// Example:
+    ///
+    /// let a = HVector::new(&[1.0, 0.0]);
+    /// assert_eq!(a.h_cosine_distance(&[0.0, 5.0]), Ok(1.0));
+    pub fn h_cosine_distance<S>(&self, other: &[S]) -> Result<f64, VectorError>
+    where
+        S: Copy + Into<f64>,
+    {
+        let dot: f64 = self.h_dot(other)?;
+        let magnitudes: f64 = self.h_magnitude() * other.h_magnitude();
+        if magnitudes == 0.0 {
+            return Err(VectorError::ZeroVector);
+        }
+        // Rounding can push the cosine slightly outside [-1, 1] for (anti)parallel vectors.
+        Ok(1.0 - (dot / magnitudes).clamp(-1.0, 1.0))
+    }
+}
+
+impl Deref for HVector {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.data
+    }
+}
+
+impl DerefMut for HVector {
+    fn deref_mut(&mut self) -> &mut [f64] {
+        &mut self.data
+    }
+}
+
+impl From<Vec<f64>> for HVector {
+    fn from(data: Vec<f64>) -> Self {
+        HVector { data }
+    }
+}
+
+impl From<HVector> for Vec<f64> {
+    fn from(vector: HVector) -> Self {
+        vector.data
+    }
+}
+
+impl FromIterator<f64> for HVector {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        HVector {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl fmt::Display for HVector {
+    /// Formats the vector like a row of `HMatrix`, for example `[1 2.5 3]`, honouring the precision if one is given.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, x) in self.data.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            match f.precision() {
+                Some(precision) => write!(f, "{:.*}", precision, x)?,
+                None => write!(f, "{}", x)?,
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+
+// Like the matrix operators, the vector operators treat a length mismatch as a programming error and panic.
+// Use the checked methods, such as `h_add`, to get a `VectorError` instead.
+
+impl Add<&HVector> for &HVector {
+    type Output = HVector;
+
+    /// Adds two vectors element-wise. Panics if the vectors do not have the same length.
+    fn add(self, other: &HVector) -> HVector {
+        match self.h_add(other) {
+            Ok(result) => result,
+            Err(_) => panic!(
+                "from: HVector Add, cannot add a vector of length {} and a vector of length {}",
+                self.len(), other.len()
+            ),
+        }
+    }
+}
+
+impl Add for HVector {
+    type Output = HVector;
+
+    fn add(self, other: HVector) -> HVector {
+        &self + &other
+    }
+}
+
+impl Sub<&HVector> for &HVector {
+    type Output = HVector;
+
+    /// Subtracts two vectors element-wise. Panics if the vectors do not have the same length.
+    fn sub(self, other: &HVector) -> HVector {
+        match self.h_sub(other) {
+            Ok(result) => result,
+            Err(_) => panic!(
+                "from: HVector Sub, cannot subtract a vector of length {} from a vector of length {}",
+                other.len(), self.len()
+            ),
+        }
+    }
+}
+
+impl Sub for HVector {
+    type Output = HVector;
+
+    fn sub(self, other: HVector) -> HVector {
+        &self - &other
+    }
+}
+
+impl Mul<&HVector> for &HVector {
+    type Output = f64;
+
+    /// Calculates the dot product. Panics if the vectors do not have the same length.
+    fn mul(self, other: &HVector) -> f64 {
+        match self.h_dot(other) {
+            Ok(result) => result,
+            Err(_) => panic!(
+                "from: HVector Mul, cannot take the dot product of a vector of length {} and a vector of length {}",
+                self.len(), other.len()
+            ),
+        }
+    }
+}
+
+impl Mul<f64> for &HVector {
+    type Output = HVector;
+
+    fn mul(self, scalar: f64) -> HVector {
+        self.data.iter().map(|x| x * scalar).collect()
+    }
+}
+
+impl Mul<f64> for HVector {
+    type Output = HVector;
+
+    fn mul(self, scalar: f64) -> HVector {
+        &self * scalar
+    }
+}
+
+impl Mul<&HVector> for f64 {
+    type Output = HVector;
+
+    fn mul(self, vector: &HVector) -> HVector {
+        vector * self
+    }
+}
+
+impl Mul<HVector> for f64 {
+    type Output = HVector;
+
+    fn mul(self, vector: HVector) -> HVector {
+        &vector * self
+    }
+}
+
+impl Div<f64> for &HVector {
+    type Output = HVector;
+
+    fn div(self, scalar: f64) -> HVector {
+        self.data.iter().map(|x| x / scalar).collect()
+    }
+}
+
+impl Div<f64> for HVector {
+    type Output = HVector;
+
+    fn div(self, scalar: f64) -> HVector {
+        &self / scalar
+    }
+}
+
+impl Neg for &HVector {
+    type Output = HVector;
+
+    fn neg(self) -> HVector {
+        self.data.iter().map(|x| -x).collect()
+    }
+}
+
+impl Neg for HVector {
+    type Output = HVector;
+
+    fn neg(self) -> HVector {
+        -&self
+    }
+}
+
+
+/// Hadamard product (element-wise multiplication) of two vectors.
+/// Both vectors must have the same length.
+/// Formula: C = A ⊙ B, where C[i] = A[i] * B[i]
+///
+/// Example:
+/// 
+/// let vec1 = vec![1.0, 2.0, 3.0];
+/// let vec2 = vec![4.0, 5.0, 6.0];
+/// let result = h_hadamard(&vec1, &vec2);
+/// assert_eq!(result, vec![4.0, 10.0, 18.0]);
+/// The Hadamard product is calculated as (1*4, 2*5, 3*6) = (4.0, 10.0, 18.0).
+
+pub fn h_hadamard<I, T>(vec1: &[I], vec2: &[T]) -> Vec<f64> 
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    let mut new_vec: Vec<f64> = Vec::new();
+    if vec1.len() != vec2.len() {
+        panic!("from: h_hadamard, vectors must have the same length");
+    }
+    for (a, b) in zip(vec1, vec2) {
+        new_vec.push((*a).into() * (*b).into())
+    }
+    return new_vec;
+}
+
+
+/// Calculates the dot product of two vectors. The vectors must have the same length.
+/// Formula: C = A · B, where C = Σ(A[i] * B[i])
+///
+/// Example:
+/// 
+/// let vec1 = vec![1.0, 2.0, 3.0];
+/// let vec2 = vec![4.0, 5.0, 6.0];
+/// let result = h_dot(&vec1, &vec2);
+/// assert_eq!(result, 32.0);
+/// The dot product is calculated as (1*4) + (2*5) + (3*6) = 4 + 10 + 18 = 32.0.
+
+pub fn h_dot<A, B>(vec1: &[A], vec2: &[B]) -> f64 
+where 
+    A: Copy + Into<f64>,
+    B: Copy + Into<f64>,
+{
+    if vec1.len() != vec2.len() {
+        panic!("h_dot: the two vectors do not have the same length");
+    }
+    let mut sum: f64 = 0.0;
+    for (a, b) in zip(vec1, vec2) {
+        sum += (*a).into()*(*b).into();
+    }
+    return sum;
+}
+
+
+/// Trait that provides the Euclidean magnitude (length) of a vector.
+///
+/// The magnitude of a vector `A` is defined as:
+/// ```text
+/// ||A|| = sqrt(A[0]^2 + A[1]^2 + ... + A[n]^2)
+/// ```
+///
+/// This is simply the Euclidean norm and corresponds to the length of the
+/// vector in n‑dimensional space.
+///
+/// # Examples
+///
+/// 
+/// let v = vec![3.0, 4.0];
+/// assert_eq!(v.h_magnitude(), 5.0);
+/// 
+///
+/// The trait is implemented for slices whose element type can be converted into
+/// `f64`.
+pub trait Magnitude {
+    fn h_magnitude(&self) -> f64;
+}
+
+impl<T> Magnitude for [T] 
+where 
+    T: Copy + Into<f64>,
+{
+    fn h_magnitude(&self) -> f64 {
+        let sum_of_squares: f64 = self.iter().map(|x| {
+            let v: f64 = (*x).into();
+            v * v
+        }).sum();
+        sum_of_squares.sqrt()
+    }
+}
+
+
+
+/// Adds two vectors element-wise. The vectors must have the same length.
+/// Formula: C = A + B, where C[i] = A[i] + B[i]
+///
+/// Example:
+/// 
+/// let vec1 = vec![1.0, 2.0, 3.0];
+/// let vec2 = vec![4.0, 5.0, 6.0];
+/// let result = h_vector_add(&vec1, &vec2);
+/// assert_eq!(result, vec![5.0, 7.0, 9.0]);
+/// The vector addition is calculated as (1+4, 2+5, 3+6) = (5.0, 7.0, 9.0).
+
+pub fn h_vector_add<T, I>(vec1: &[T], vec2: &[I]) -> Vec<f64>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    if vec1.len() != vec2.len() {
+        panic!("from: h_vector_add, the vectors do not have the same length");
+    }
+
+    zip(vec1, vec2)
+        .map(|(a, b)| (*a).into() + (*b).into())
+        .collect()
+}
+
+/// Subtracts the second vector from the first, element‑wise. Both vectors must have
+/// the same length.
+///
+/// Example:
+/// 
+/// let vec1 = vec![5.0, 7.0, 9.0];
+/// let vec2 = vec![1.0, 2.0, 3.0];
+/// let result = h_vector_sub(&vec1, &vec2);
+/// assert_eq!(result, vec![4.0, 5.0, 6.0]);
+/// 
+pub fn h_vector_sub<T, I>(vec1: &[T], vec2: &[I]) -> Vec<f64>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    if vec1.len() != vec2.len() {
+        panic!("from: h_vector_sub, the vectors do not have the same length");
+    }
+
+    zip(vec1, vec2)
+        .map(|(a, b)| (*a).into() - (*b).into())
+        .collect()
+}
+
+/// Multiply every element of a slice by a scalar value.
+///
+/// This trait is implemented for slice types so that you can write
+/// `my_slice.h_vector_scalar_mult(k)` and receive a new `Vec<f64>` containing
+/// each element of `my_slice` multiplied by `k`.
+///
+/// # Example
+/// let v = vec![1.0, 2.0, 3.0];
+/// assert_eq!(v.h_vector_scalar_mult(2.0), vec![2.0, 4.0, 6.0]);
+
+pub trait VectorScalarMultiply<S>
+where
+    S: Copy + Into<f64>,
+{
+    fn h_vector_scalar_mult(&self, scalar: S) -> Vec<f64>;
+}
+
+impl<S, T> VectorScalarMultiply<S> for [T]
+where 
+    S: Copy + Into<f64>,
+    T: Copy + Into<f64>,
+{
+    fn h_vector_scalar_mult(&self, scalar: S) -> Vec<f64> {
+        self.iter().map(|x| (*x).into() * scalar.into()).collect()
+    }
+}
+
+
+
+/// Divide every element of a slice by a scalar value.
+///
+/// Similar to [`VectorScalarMultiply`], this trait is implemented for slice types
+/// and returns a `Vec<f64>` containing each element divided by the provided
+/// scalar.
+///
+/// # Example
+///
+/// 
+/// let v = vec![4.0, 6.0, 8.0];
+/// assert_eq!(v.h_vector_scalar_div(2.0), vec![2.0, 3.0, 4.0]);
+/// The vector scalar division is calculated as (4/2, 6/2, 8/2) = (2.0, 3.0, 4.0).
+pub trait VectorScalarDivision<S>
 where
     S: Copy + Into<f64>,
 {
@@ -398,20 +3333,21 @@ where
     }
 }
 
-/// Specifies the unit in which an angle is returned.
-#[derive(Debug, Eq, PartialEq)]
+/// Specifies the unit in which an angle is given or returned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Measurement {
-    /// Return the angle in radians.
+    /// The angle is in radians.
     Radians,
-    /// Return the angle in degrees.
+    /// The angle is in degrees.
     Degrees,
 }
 
-/// Calculates the angle between two 2‑dimensional vectors.
+/// Calculates the angle between two vectors with any (equal) number of components.
 ///
-/// Both `vec1` and `vec2` must have exactly two components; otherwise,
-/// the function returns `None`. If either vector has zero magnitude the
-/// angle is undefined and `None` is returned as well.
+/// If the vectors are empty or have different lengths, the function returns `None`.
+/// If either vector has zero magnitude the angle is undefined and `None` is returned as well.
+/// The cosine is clamped to `[-1, 1]` before `acos`, so parallel vectors give exactly 0 (or π)
+/// instead of `NaN` when rounding pushes it slightly past 1.
 ///
 /// The result is returned in radians or degrees depending on the
 /// `return_measurement` parameter.
@@ -424,26 +3360,211 @@ pub enum Measurement {
 /// let b = vec![1.0, 0.0];
 /// assert_eq!(h_vectors_angle(&a, &b, Measurement::Degrees).unwrap(), 90.0);
 /// The angle between the vectors (0, 1) and (1, 0) is 90 degrees because they are perpendicular to each other.
+/// assert_eq!(h_vectors_angle(&[1, 1, 1], &[2, 2, 2], Measurement::Radians).unwrap(), 0.0);
 pub fn h_vectors_angle<T, I>(vec1: &[T], vec2: &[I], return_measurement: Measurement) -> Option<f64> 
 where   
     T: Copy + Into<f64>,
-    I: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    if vec1.is_empty() || vec1.len() != vec2.len() {
+        return None;
+    }
+    let vec1_magnitude: f64 = vec1.h_magnitude();
+    let vec2_magnitude: f64 = vec2.h_magnitude();
+
+    if vec1_magnitude == 0.0 || vec2_magnitude == 0.0 {
+        return None;
+    }
+
+    let cosine: f64 = (h_dot(vec1, vec2) / (vec1_magnitude * vec2_magnitude)).clamp(-1.0, 1.0);
+    let angle_between: f64 = cosine.acos();
+    match return_measurement {
+        Measurement::Radians => return Some(angle_between),
+        Measurement::Degrees => return Some(angle_between.to_degrees()),
+    }
+}
+
+
+/// Calculates the cross product of two 3-dimensional vectors, a vector perpendicular to both
+/// whose length is the area of the parallelogram they span.
+/// Formula: A × B = (A[1]B[2] - A[2]B[1], A[2]B[0] - A[0]B[2], A[0]B[1] - A[1]B[0])
+/// If either vector does not have exactly three components, the function returns `None`.
+///
+/// Example:
+///
+/// let x = vec![1.0, 0.0, 0.0];
+/// let y = vec![0.0, 1.0, 0.0];
+/// assert_eq!(h_cross(&x, &y), Some(vec![0.0, 0.0, 1.0]));
+pub fn h_cross<T, I>(vec1: &[T], vec2: &[I]) -> Option<Vec<f64>>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    if vec1.len() != 3 || vec2.len() != 3 {
+        return None;
+    }
+    let a: Vec<f64> = vec1.iter().map(|x| (*x).into()).collect();
+    let b: Vec<f64> = vec2.iter().map(|x| (*x).into()).collect();
+    Some(vec![
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ])
+}
+
+
+/// Calculates the scalar triple product `A · (B × C)` of three 3-dimensional vectors, which is the signed
+/// volume of the parallelepiped they span. It is zero when the vectors lie in a common plane.
+/// If any vector does not have exactly three components, the function returns `None`.
+///
+/// Example:
+///
+/// let volume = h_triple_product(&[1, 0, 0], &[0, 2, 0], &[0, 0, 3]).unwrap();
+/// assert_eq!(volume, 6.0);
+pub fn h_triple_product<A, B, C>(vec1: &[A], vec2: &[B], vec3: &[C]) -> Option<f64>
+where
+    A: Copy + Into<f64>,
+    B: Copy + Into<f64>,
+    C: Copy + Into<f64>,
+{
+    if vec1.len() != 3 {
+        return None;
+    }
+    Some(h_dot(vec1, &h_cross(vec2, vec3)?))
+}
+
+
+/// Projects `vec` onto the line spanned by `onto`, giving the component of `vec` that points along `onto`.
+/// Formula: proj = ((A · B) / (B · B)) * B
+/// If the vectors have different lengths or `onto` is the zero vector, the function returns `None`.
+///
+/// Example:
+///
+/// let projection = h_projection(&[2.0, 3.0], &[1.0, 0.0]).unwrap();
+/// assert_eq!(projection, vec![2.0, 0.0]);
+pub fn h_projection<T, I>(vec: &[T], onto: &[I]) -> Option<Vec<f64>>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    if vec.len() != onto.len() {
+        return None;
+    }
+    let onto_squared: f64 = h_dot(onto, onto);
+    if onto_squared == 0.0 {
+        return None;
+    }
+    Some(onto.h_vector_scalar_mult(h_dot(vec, onto) / onto_squared))
+}
+
+
+/// Calculates the rejection of `vec` from `onto`, the component of `vec` perpendicular to `onto`,
+/// so that `projection + rejection = vec`.
+/// If the vectors have different lengths or `onto` is the zero vector, the function returns `None`.
+///
+/// Example:
+///
+/// let rejection = h_rejection(&[2.0, 3.0], &[1.0, 0.0]).unwrap();
+/// assert_eq!(rejection, vec![0.0, 3.0]);
+pub fn h_rejection<T, I>(vec: &[T], onto: &[I]) -> Option<Vec<f64>>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    Some(h_vector_sub(vec, &h_projection(vec, onto)?))
+}
+
+
+/// Projects `vec` onto the subspace spanned by `basis`, giving the closest point to `vec` in that subspace.
+/// The basis vectors do not have to be orthogonal or even independent: the projection is computed as
+/// `A A⁺ vec`, where `A` has the basis vectors as columns and `A⁺` is its pseudo-inverse (see `h_pseudo_inverse`).
+/// If `basis` is empty or its vectors do not all have the same length as `vec`, the function returns `None`.
+///
+/// Example:
+///
+/// let xy_plane = vec![vec![1.0, 1.0, 0.0], vec![1.0, -1.0, 0.0]];
+/// let projection = h_subspace_projection(&[3.0, 4.0, 5.0], &xy_plane).unwrap();
+/// projection is approximately [3.0, 4.0, 0.0]
+pub fn h_subspace_projection<T, I>(vec: &[T], basis: &[Vec<I>]) -> Option<Vec<f64>>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    if basis.iter().any(|b| b.len() != vec.len()) {
+        return None;
+    }
+    let a: HMatrix<I> = HMatrix::new_from_cols(basis)?;
+    let coefficients: Vec<f64> = vec.h_linear_transform(&a.h_pseudo_inverse()?)?;
+    coefficients.h_linear_transform(&a)
+}
+
+
+/// Calculates the rejection of `vec` from the subspace spanned by `basis`, the component of `vec`
+/// perpendicular to every basis vector, so that `h_subspace_projection + h_subspace_rejection = vec`.
+/// If `basis` is empty or its vectors do not all have the same length as `vec`, the function returns `None`.
+pub fn h_subspace_rejection<T, I>(vec: &[T], basis: &[Vec<I>]) -> Option<Vec<f64>>
+where
+    T: Copy + Into<f64>,
+    I: Copy + Into<f64>,
+{
+    Some(h_vector_sub(vec, &h_subspace_projection(vec, basis)?))
+}
+
+
+/// Computes an orthonormal basis of the span of `vectors` with the modified Gram-Schmidt process.
+/// The basis vectors are unit length, orthogonal to each other, and span the same space as `vectors`.
+/// Vectors that are (numerically) linearly dependent on the ones before them are skipped, so the basis
+/// can be shorter than the input. If the vectors do not all have the same length, the function returns `None`.
+///
+/// Example:
+///
+/// let vectors = vec![vec![3.0, 4.0], vec![6.0, 8.0], vec![1.0, 0.0]];
+/// let basis = h_gram_schmidt(&vectors).unwrap();
+/// basis is approximately [[0.6, 0.8], [0.8, -0.6]], since the second vector is twice the first.
+pub fn h_gram_schmidt<T>(vectors: &[Vec<T>]) -> Option<Vec<Vec<f64>>>
+where
+    T: Copy + Into<f64>,
+{
+    let len: usize = vectors.first().map_or(0, |v| v.len());
+    if vectors.iter().any(|v| v.len() != len) {
+        return None;
+    }
+    Some(modified_gram_schmidt(
+        vectors.iter().map(|v| v.iter().map(|x| (*x).into()).collect()).collect(),
+    ))
+}
+
+
+/// Computes an orthonormal basis of the orthogonal complement of the span of `vectors`, every vector that is
+/// perpendicular to all of them. This is the null space of the matrix that has `vectors` as its rows.
+/// If `vectors` is empty or the vectors do not all have the same length, the function returns `None`.
+///
+/// Example:
+///
+/// let complement = h_orthogonal_complement(&[vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]).unwrap();
+/// assert_eq!(complement, vec![vec![0.0, 0.0, 1.0]]);
+pub fn h_orthogonal_complement<T>(vectors: &[Vec<T>]) -> Option<Vec<Vec<f64>>>
+where
+    T: Copy + Into<f64>,
 {
-    if vec1.len() != 2 || vec2.len() != 2 {
-        return None;
-    }
-    let vec1_magnitude: f64 = vec1.h_magnitude();
-    let vec2_magnitude: f64 = vec2.h_magnitude();
+    let len: usize = vectors.first()?.len();
+    Some(orthogonal_complement(h_gram_schmidt(vectors)?, len))
+}
 
-    if vec1_magnitude == 0.0 || vec2_magnitude == 0.0 {
-        return None;
-    }
 
-    let angle_between: f64 = ((h_dot(vec1, vec2))/(vec1_magnitude*vec2_magnitude)).acos();
-    match return_measurement {
-        Measurement::Radians => return Some(angle_between),
-        Measurement::Degrees => return Some(angle_between.to_degrees()),
-    }
+/// Checks whether `vectors` are linearly independent, meaning that none of them is a linear combination of the others.
+/// A vector is treated as dependent if less than `sqrt(ε)` of its magnitude remains after removing its projection
+/// onto the earlier vectors. If the vectors do not all have the same length, the function returns `false`.
+///
+/// Example:
+///
+/// assert!(h_are_linearly_independent(&[vec![1, 0, 0], vec![1, 1, 0]]));
+/// assert!(!h_are_linearly_independent(&[vec![1, 2], vec![2, 4]]));
+pub fn h_are_linearly_independent<T>(vectors: &[Vec<T>]) -> bool
+where
+    T: Copy + Into<f64>,
+{
+    h_gram_schmidt(vectors).is_some_and(|basis| basis.len() == vectors.len())
 }
 
 
@@ -506,13 +3627,18 @@ where
 /// # Note
 /// The result is always `HMatrix<f64>` regardless of input type, because all
 /// values are converted to `f64` during the dot product calculations.
+/// The `*` operator computes the same product with the operands in reading order: `&matrix2 * &matrix1`.
+///
+/// # Performance
+/// The product is computed with a cache-blocked kernel that converts every element to `f64` only once.
+/// With the `parallel` feature enabled, large products are also split across threads.
 ///
 /// # Example
 /// 
 /// let m1 = HMatrix::new_from_rows(&[vec![1, 2], vec![3, 4]]).unwrap();
 /// let m2 = HMatrix::new_from_rows(&[vec![5, 6], vec![7, 8]]).unwrap();
 /// let result = h_linear_composition(&m2, &m1).unwrap();
-/// result is a 2x2 matrix: [[19, 22], [43, 50]]
+/// result is a 2x2 matrix: [[23, 34], [31, 46]]
 pub fn h_linear_composition<T>(matrix2: &HMatrix<T>, matrix1: &HMatrix<T>) -> Option<HMatrix<f64>>
 where 
     T: Copy + Into<f64>,
@@ -520,14 +3646,7 @@ where
     if matrix2.row_size != matrix1.columm_size {
         return None;
     }
-    let mut new_matrix: HMatrix<f64> = HMatrix::new();
-    for i in 0..matrix1.row_size {
-        // this i guaranteed to be safe because of the rules already in place
-        if new_matrix.add_col(matrix1.get_col(i).unwrap().h_linear_transform(&matrix2).unwrap()).is_err() {
-            panic!("Critical failure, HMatrix Struct Logic has an error");
-        }
-    }
-    return Some(new_matrix);
+    Some(matrix2 * matrix1)
 }
 
 
@@ -596,11 +3715,799 @@ mod tests {
         assert_eq!(h_vectors_angle(&vec1, &vec2, Measurement::Degrees).unwrap_or(0.0), 90.0);
     }
 
+    #[test]
+    fn test_vectors_angle_n_dimensional() {
+        assert_eq!(h_vectors_angle(&[1, 1, 1], &[2, 2, 2], Measurement::Radians), Some(0.0));
+        assert_eq!(h_vectors_angle(&[0.1, 0.2, 0.3], &[-0.3, -0.6, -0.9], Measurement::Radians), Some(std::f64::consts::PI));
+        let angle: f64 = h_vectors_angle(&[1, 0, 0, 0], &[1, 1, 0, 0], Measurement::Degrees).unwrap();
+        assert!((angle - 45.0).abs() < 1e-12);
+        assert_eq!(h_vectors_angle(&[1, 0, 0], &[1, 0], Measurement::Radians), None);
+        assert_eq!(h_vectors_angle(&[0, 0, 0], &[1, 0, 0], Measurement::Radians), None);
+    }
+
+    #[test]
+    fn test_h_cross_and_triple_product() {
+        assert_eq!(h_cross(&[1, 0, 0], &[0, 1, 0]), Some(vec![0.0, 0.0, 1.0]));
+        assert_eq!(h_cross(&[2.0, 3.0, 4.0], &[5.0, 6.0, 7.0]), Some(vec![-3.0, 6.0, -3.0]));
+        assert_eq!(h_cross(&[1, 0], &[0, 1]), None);
+        assert_eq!(h_triple_product(&[1, 0, 0], &[0, 2, 0], &[0, 0, 3]), Some(6.0));
+        assert_eq!(h_triple_product(&[1, 2, 3], &[4, 5, 6], &[5, 7, 9]), Some(0.0));
+    }
+
+    #[test]
+    fn test_h_projection_and_rejection() {
+        assert_eq!(h_projection(&[2.0, 3.0], &[1.0, 0.0]), Some(vec![2.0, 0.0]));
+        assert_eq!(h_rejection(&[2.0, 3.0], &[1.0, 0.0]), Some(vec![0.0, 3.0]));
+        assert_eq!(h_projection(&[1, 2, 3], &[1, 1, 1]), Some(vec![2.0, 2.0, 2.0]));
+        assert_eq!(h_projection(&[1, 2], &[0, 0]), None);
+        assert_eq!(h_rejection(&[1, 2], &[1, 2, 3]), None);
+
+        // The third basis vector is a multiple of the first, so the subspace is still the xy-plane.
+        let basis = vec![vec![1.0, 1.0, 0.0], vec![1.0, -1.0, 0.0], vec![2.0, 2.0, 0.0]];
+        let projection = h_subspace_projection(&[3.0, 4.0, 5.0], &basis).unwrap();
+        let rejection = h_subspace_rejection(&[3.0, 4.0, 5.0], &basis).unwrap();
+        assert!(h_vector_sub(&projection, &[3.0, 4.0, 0.0]).h_magnitude() < 1e-12);
+        assert!(h_vector_sub(&rejection, &[0.0, 0.0, 5.0]).h_magnitude() < 1e-12);
+        assert_eq!(h_subspace_projection(&[1.0, 2.0], &basis), None);
+    }
+
+    #[test]
+    fn test_h_subspace_projection_non_square_basis() {
+        let basis = vec![vec![1.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]];
+        let projection = h_subspace_projection(&[1.0, 2.0, 3.0], &basis).unwrap();
+        assert!(h_vector_sub(&projection, &[2.0, 2.0, 2.0]).h_magnitude() < 1e-12);
+    }
+
     #[test]
     fn test_h_linear_transform() {
         let vec = vec![1.0, 2.0];
         let matrix = HMatrix::new_from_rows(&vec![vec![1.0, 0.0], vec![0.0, 1.0]]).unwrap();
         let result = vec.h_linear_transform(&matrix);
         assert_eq!(result.unwrap_or_else(|| vec![]), vec![1.0, 2.0]);    }
-}
 
+    #[test]
+    fn test_h_linear_composition() {
+        let m1 = HMatrix::new_from_rows(&[vec![1, 2], vec![3, 4]]).unwrap();
+        let m2 = HMatrix::new_from_rows(&[vec![5, 6], vec![7, 8]]).unwrap();
+        let result = h_linear_composition(&m2, &m1).unwrap();
+        assert_eq!(result.data, vec![23.0, 34.0, 31.0, 46.0]);
+    }
+
+    #[test]
+    fn test_h_determinant() {
+        let matrix = HMatrix::new_from_rows(&[vec![2.0, 1.0], vec![4.0, 3.0]]).unwrap();
+        assert!((matrix.h_determinant().unwrap() - 2.0).abs() < 1e-12);
+
+        let matrix = HMatrix::new_from_rows(&[vec![0, 2, 1], vec![3, -1, 2], vec![1, 1, 1]]).unwrap();
+        assert!((matrix.h_determinant().unwrap() - 2.0).abs() < 1e-12);
+
+        let singular = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert_eq!(singular.h_determinant(), Some(0.0));
+
+        let not_square = HMatrix::new_from_rows(&[vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]).unwrap();
+        assert_eq!(not_square.h_determinant(), None);
+    }
+
+    #[test]
+    fn test_h_inverse() {
+        let matrix = HMatrix::new_from_rows(&[vec![4.0, 7.0], vec![2.0, 6.0]]).unwrap();
+        let inverse = matrix.h_inverse().unwrap();
+        let expected = [0.6, -0.7, -0.2, 0.4];
+        for (a, b) in zip(&inverse.data, &expected) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        let singular = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert!(singular.h_inverse().is_none());
+    }
+
+    #[test]
+    fn test_h_trace_and_norms() {
+        let matrix = h_matrix![1, -2; -3, 4];
+        assert_eq!(matrix.h_trace(), Some(5.0));
+        assert_eq!(h_matrix![1, 2, 3].h_trace(), None);
+        assert_eq!(matrix.h_frobenius_norm(), 30f64.sqrt());
+        assert_eq!(matrix.h_one_norm(), 6.0);
+        assert_eq!(matrix.h_infinity_norm(), 7.0);
+    }
+
+    #[test]
+    fn test_h_pow() {
+        let fibonacci = h_matrix![1, 1; 1, 0];
+        assert_eq!(fibonacci.h_pow(10).unwrap(), h_matrix![89.0, 55.0; 55.0, 34.0]);
+        assert_eq!(fibonacci.h_pow(0).unwrap(), HMatrix::<f64>::identity(2));
+        assert!((&fibonacci.h_pow(-3).unwrap() * &fibonacci.h_pow(3).unwrap()).h_approx_eq(&HMatrix::<f64>::identity(2), 1e-12));
+        assert!(h_matrix![1, 2; 2, 4].h_pow(-1).is_none());
+        assert!(h_matrix![1, 2, 3].h_pow(2).is_none());
+
+        // A regular Markov chain converges to its stationary distribution [5/6, 1/6] from either state.
+        let transition = h_matrix![0.9, 0.1; 0.5, 0.5];
+        let limit = transition.h_pow(200).unwrap();
+        assert!(limit.h_approx_eq(&h_matrix![5.0 / 6.0, 1.0 / 6.0; 5.0 / 6.0, 1.0 / 6.0], 1e-12));
+    }
+
+    #[test]
+    fn test_h_exp() {
+        let diagonal = h_matrix![1.0, 0.0; 0.0, -2.0];
+        assert!(diagonal.h_exp().unwrap().h_approx_eq(&h_matrix![1f64.exp(), 0.0; 0.0, (-2f64).exp()], 1e-13));
+
+        let rotation = (&h_matrix![0.0, -1.0; 1.0, 0.0] * std::f64::consts::FRAC_PI_2).h_exp().unwrap();
+        assert!(rotation.h_approx_eq(&h_matrix![0.0, -1.0; 1.0, 0.0], 1e-13));
+
+        // A nilpotent matrix has a finite series: e^N = I + N + N²/2.
+        let nilpotent = h_matrix![0.0, 10.0, 0.0; 0.0, 0.0, 10.0; 0.0, 0.0, 0.0];
+        let expected = h_matrix![1.0, 10.0, 50.0; 0.0, 1.0, 10.0; 0.0, 0.0, 1.0];
+        assert!(nilpotent.h_exp().unwrap().h_approx_eq(&expected, 1e-10));
+
+        assert_eq!(HMatrix::<f64>::zeros(2, 2).h_exp().unwrap(), HMatrix::<f64>::identity(2));
+        assert!(h_matrix![1, 2].h_exp().is_none());
+        assert!(h_matrix![f64::INFINITY, 0.0; 0.0, 1.0].h_exp().is_none());
+        assert!(h_matrix![f64::NAN, 0.0; 0.0, 1.0].h_exp().is_none());
+        assert!(h_matrix![f64::MAX, 0.0; 0.0, 1.0].h_exp().is_none());
+        assert!(h_matrix![f64::MAX / 2.0, f64::MAX / 2.0; 0.0, 1.0].h_exp().is_none());
+        assert!(h_matrix![-1e300, 0.0; 0.0, 0.0].h_exp().is_some());
+    }
+
+    #[test]
+    fn test_h_kronecker_and_stack() {
+        let a = h_matrix![1, 2; 3, 4];
+        let b = h_matrix![0, 1; 1, 0];
+        let product = a.h_kronecker(&b);
+        assert_eq!(product.shape(), (4, 4));
+        assert_eq!(product.get_row(1), Some(vec![1.0, 0.0, 2.0, 0.0]));
+        assert_eq!(product.get_row(2), Some(vec![0.0, 3.0, 0.0, 4.0]));
+
+        assert_eq!(a.hstack(&b).unwrap(), h_matrix![1, 2, 0, 1; 3, 4, 1, 0]);
+        assert_eq!(a.vstack(&b).unwrap(), h_matrix![1, 2; 3, 4; 0, 1; 1, 0]);
+        assert_eq!(HMatrix::new().hstack(&a).unwrap(), a);
+        assert!(a.hstack(&h_matrix![1, 2, 3]).is_none());
+        assert!(a.vstack(&h_matrix![1; 2]).is_none());
+
+        // A matrix without columns still has a row count that has to match.
+        let trivial: HMatrix<f64> = h_matrix![1.0, 0.0, 0.0; 0.0, 1.0, 0.0; 0.0, 0.0, 1.0].h_null_space();
+        assert_eq!(trivial.shape(), (3, 0));
+        let square = h_matrix![1.0, 2.0; 3.0, 4.0];
+        assert!(square.hstack(&trivial).is_none());
+        assert!(trivial.hstack(&square).is_none());
+        let column = h_matrix![1.0; 2.0; 3.0];
+        assert_eq!(trivial.hstack(&column).unwrap(), column);
+        assert_eq!(column.hstack(&trivial).unwrap(), column);
+        assert!(square.vstack(&trivial).is_none());
+    }
+
+    #[test]
+    fn test_h_rank() {
+        let full = HMatrix::new_from_rows(&[vec![1, 0, 2], vec![0, 1, 3]]).unwrap();
+        assert_eq!(full.h_rank(), 2);
+
+        let deficient = HMatrix::new_from_rows(&[vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0], vec![1.0, 0.0, 1.0]]).unwrap();
+        assert_eq!(deficient.h_rank(), 2);
+
+        let zero = HMatrix::new_from_rows(&[vec![0.0, 0.0], vec![0.0, 0.0]]).unwrap();
+        assert_eq!(zero.h_rank(), 0);
+    }
+
+    #[test]
+    fn test_h_solve() {
+        let a = HMatrix::new_from_rows(&[vec![2.0, 1.0, -1.0], vec![-3.0, -1.0, 2.0], vec![-2.0, 1.0, 2.0]]).unwrap();
+        let x = a.h_solve(&[8, -11, -3]).unwrap();
+        for (a, b) in zip(&x, &[2.0, 3.0, -1.0]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        let singular = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert_eq!(singular.h_solve(&[1.0, 2.0]), Err(LinearSystemError::Singular));
+        assert_eq!(
+            singular.h_solve(&[1.0, 2.0, 3.0]),
+            Err(LinearSystemError::DimensionMismatch { equations: 2, rhs_len: 3 })
+        );
+    }
+
+    #[test]
+    fn test_h_solve_non_square() {
+        let tall = HMatrix::new_from_rows(&[vec![1.0, 1.0], vec![1.0, -1.0], vec![2.0, 1.0]]).unwrap();
+        let x = tall.h_solve(&[3.0, 1.0, 5.0]).unwrap();
+        assert!((x[0] - 2.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+        assert_eq!(
+            tall.h_solve(&[3.0, 1.0, 6.0]),
+            Err(LinearSystemError::Overdetermined { equations: 3, unknowns: 2 })
+        );
+
+        let wide = HMatrix::new_from_rows(&[vec![1.0, 2.0, 3.0], vec![0.0, 1.0, 1.0]]).unwrap();
+        assert_eq!(
+            wide.h_solve(&[1.0, 2.0]),
+            Err(LinearSystemError::Underdetermined { rank: 2, unknowns: 3 })
+        );
+
+        // Tall and rank-deficient: inconsistent systems are overdetermined, consistent ones underdetermined.
+        let dependent = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]]).unwrap();
+        assert_eq!(
+            dependent.h_solve(&[1.0, 2.0, 4.0]),
+            Err(LinearSystemError::Overdetermined { equations: 3, unknowns: 2 })
+        );
+        assert_eq!(
+            dependent.h_solve(&[1.0, 2.0, 3.0]),
+            Err(LinearSystemError::Underdetermined { rank: 1, unknowns: 2 })
+        );
+    }
+
+    #[test]
+    fn test_h_solve_multiple() {
+        let a = HMatrix::new_from_rows(&[vec![2.0, 0.0], vec![1.0, 4.0]]).unwrap();
+        let b = HMatrix::new_from_rows(&[vec![2.0, 4.0], vec![5.0, 10.0]]).unwrap();
+        let x = a.h_solve_multiple(&b).unwrap();
+        for (a, b) in zip(&x.data, &[1.0, 2.0, 1.0, 2.0]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    fn assert_matrix_close(matrix: &HMatrix<f64>, expected: &[f64]) {
+        assert_eq!(matrix.data.len(), expected.len());
+        for (a, b) in zip(&matrix.data, expected) {
+            assert!((a - b).abs() < 1e-10, "{} != {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_h_lu() {
+        let a = HMatrix::new_from_rows(&[vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0], vec![7.0, 8.0, 10.0]]).unwrap();
+        let lu = a.h_lu().unwrap();
+        let pa = h_linear_composition(&lu.p(), &a).unwrap();
+        let product = h_linear_composition(&lu.l(), &lu.u()).unwrap();
+        assert_matrix_close(&product, &pa.data);
+        assert_eq!(lu.permutation()[0], 2);
+        assert!((lu.h_determinant() - -3.0).abs() < 1e-12);
+
+        let x = lu.h_solve(&[6.0, 15.0, 25.0]).unwrap();
+        let y = lu.h_solve(&[1.0, 4.0, 7.0]).unwrap();
+        for (a, b) in zip(x.iter().chain(&y), &[1.0, 1.0, 1.0, 1.0, 0.0, 0.0]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+
+        let singular = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        let lu = singular.h_lu().unwrap();
+        assert!(lu.is_singular());
+        assert_eq!(lu.h_solve(&[1.0, 2.0]), Err(LinearSystemError::Singular));
+    }
+
+    #[test]
+    fn test_h_qr() {
+        let a = HMatrix::new_from_rows(&[vec![12.0, -51.0, 4.0], vec![6.0, 167.0, -68.0], vec![-4.0, 24.0, -41.0]]).unwrap();
+        let qr = a.h_qr().unwrap();
+        assert_matrix_close(&h_linear_composition(qr.q(), qr.r()).unwrap(), &a.data);
+
+        let mut q_transpose: HMatrix<f64> = HMatrix::new();
+        for i in 0..3 {
+            q_transpose.add_col(qr.q().get_row(i).unwrap()).unwrap();
+        }
+        let identity = h_linear_composition(&q_transpose, qr.q()).unwrap();
+        assert_matrix_close(&identity, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        for r in 1..3 {
+            for c in 0..r {
+                assert_eq!(qr.r().get(r, c), Some(0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_h_qr_least_squares() {
+        // Fit y = c0 + c1 * x through (0, 1), (1, 3), (2, 5), (3, 7.5).
+        let a = HMatrix::new_from_rows(&[vec![1.0, 0.0], vec![1.0, 1.0], vec![1.0, 2.0], vec![1.0, 3.0]]).unwrap();
+        let x = a.h_qr().unwrap().h_solve(&[1.0, 3.0, 5.0, 7.5]).unwrap();
+        assert!((x[0] - 0.9).abs() < 1e-12);
+        assert!((x[1] - 2.15).abs() < 1e-12);
+
+        let dependent = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]]).unwrap();
+        assert_eq!(
+            dependent.h_qr().unwrap().h_solve(&[1.0, 2.0, 3.0]),
+            Err(LinearSystemError::Underdetermined { rank: 1, unknowns: 2 })
+        );
+    }
+
+    #[test]
+    fn test_h_least_squares() {
+        let x = [0.0, 1.0, 2.0, 3.0];
+        let y = [1.0, 3.0, 5.0, 7.5];
+        let design = HMatrix::from_fn(4, 2, |i, j| if j == 0 { 1.0 } else { x[i] });
+        let fit = design.h_least_squares(&y).unwrap();
+        assert!(h_vector_sub(fit.coefficients(), &[0.9, 2.15]).h_magnitude() < 1e-12);
+        assert!(h_vector_sub(fit.residuals(), &[0.1, -0.05, -0.2, 0.15]).h_magnitude() < 1e-12);
+        assert!((fit.residual_sum_of_squares() - 0.075).abs() < 1e-12);
+        // SS_tot = 23.1875 around the mean 4.125.
+        assert!((fit.r_squared() - (1.0 - 0.075 / 23.1875)).abs() < 1e-12);
+        assert!((fit.h_predict(&[1.0, 4.0]).unwrap() - 9.5).abs() < 1e-12);
+        assert_eq!(fit.h_predict(&[1.0]), None);
+
+        let exact = h_matrix![1, 0; 1, 1; 1, 2].h_least_squares(&[2, 4, 6]).unwrap();
+        assert!((exact.r_squared() - 1.0).abs() < 1e-12);
+        assert!(exact.fitted_values().iter().zip([2.0, 4.0, 6.0]).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        assert_eq!(
+            design.h_least_squares(&[1.0, 2.0]).err(),
+            Some(LinearSystemError::DimensionMismatch { equations: 4, rhs_len: 2 })
+        );
+        assert_eq!(
+            h_matrix![1, 2; 2, 4; 3, 6].h_least_squares(&[1, 2, 3]).err(),
+            Some(LinearSystemError::Underdetermined { rank: 1, unknowns: 2 })
+        );
+    }
+
+    #[test]
+    fn test_h_cholesky() {
+        let a = HMatrix::new_from_rows(&[vec![4.0, 12.0, -16.0], vec![12.0, 37.0, -43.0], vec![-16.0, -43.0, 98.0]]).unwrap();
+        let cholesky = a.h_cholesky().unwrap();
+        assert_matrix_close(cholesky.l(), &[2.0, 0.0, 0.0, 6.0, 1.0, 0.0, -8.0, 5.0, 3.0]);
+        assert!((cholesky.h_determinant() - 36.0).abs() < 1e-9);
+
+        let x = cholesky.h_solve(&[0.0, 6.0, 39.0]).unwrap();
+        let b = x.h_linear_transform(&a).unwrap();
+        for (a, b) in zip(&b, &[0.0, 6.0, 39.0]) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_h_is_positive_definite() {
+        let positive = HMatrix::new_from_rows(&[vec![2, -1], vec![-1, 2]]).unwrap();
+        assert!(positive.h_is_positive_definite());
+
+        let indefinite = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 1.0]]).unwrap();
+        assert!(!indefinite.h_is_positive_definite());
+
+        let not_symmetric = HMatrix::new_from_rows(&[vec![2.0, 1.0], vec![0.0, 2.0]]).unwrap();
+        assert!(!not_symmetric.h_is_positive_definite());
+    }
+
+    #[test]
+    fn test_h_symmetric_eigen() {
+        let a = HMatrix::new_from_rows(&[vec![4.0, 1.0, 2.0], vec![1.0, 3.0, 0.0], vec![2.0, 0.0, 5.0]]).unwrap();
+        let eigen = a.h_symmetric_eigen().unwrap();
+        let values = eigen.eigenvalues();
+        assert!(values[0] >= values[1] && values[1] >= values[2]);
+        assert!((values.iter().sum::<f64>() - 12.0).abs() < 1e-10);
+
+        for (i, value) in values.iter().enumerate() {
+            let vector = eigen.eigenvectors().get_col(i).unwrap();
+            let transformed = vector.h_linear_transform(&a).unwrap();
+            for (a, b) in zip(&transformed, &vector.h_vector_scalar_mult(*value)) {
+                assert!((a - b).abs() < 1e-10);
+            }
+            assert!((vector.h_magnitude() - 1.0).abs() < 1e-12);
+        }
+
+        let two = HMatrix::new_from_rows(&[vec![2, 1], vec![1, 2]]).unwrap();
+        let values = two.h_symmetric_eigen().unwrap().eigenvalues().to_vec();
+        assert!((values[0] - 3.0).abs() < 1e-12 && (values[1] - 1.0).abs() < 1e-12);
+
+        let not_symmetric = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0]]).unwrap();
+        assert!(not_symmetric.h_symmetric_eigen().is_none());
+    }
+
+    #[test]
+    fn test_h_eigenvalues() {
+        let companion = HMatrix::new_from_rows(&[vec![6.0, -11.0, 6.0], vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]]).unwrap();
+        let values = companion.h_eigenvalues().unwrap();
+        for (value, expected) in zip(&values, &[3.0, 2.0, 1.0]) {
+            match value {
+                HEigenvalue::Real(v) => assert!((v - expected).abs() < 1e-9),
+                _ => panic!("expected a real eigenvalue"),
+            }
+        }
+
+        let mixed = HMatrix::new_from_rows(&[vec![0.0, -2.0, 0.0], vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 5.0]]).unwrap();
+        let values = mixed.h_eigenvalues().unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0], HEigenvalue::Real(5.0));
+        match values[1] {
+            HEigenvalue::ComplexPair { re, im } => {
+                assert!(re.abs() < 1e-12);
+                assert!((im - 2.0f64.sqrt()).abs() < 1e-12);
+            }
+            _ => panic!("expected a complex pair"),
+        }
+    }
+
+    #[test]
+    fn test_h_eigenvalues_trace_and_determinant() {
+        let a = HMatrix::new_from_rows(&[
+            vec![2.0, -1.0, 0.5, 3.0, 1.0],
+            vec![4.0, 1.0, -2.0, 0.0, 2.5],
+            vec![-1.5, 3.0, 0.0, 1.0, -1.0],
+            vec![0.5, 2.0, 1.0, -3.0, 4.0],
+            vec![1.0, 0.0, -2.0, 2.0, 1.5],
+        ]).unwrap();
+        let mut sum: f64 = 0.0;
+        let mut product: f64 = 1.0;
+        for value in a.h_eigenvalues().unwrap() {
+            match value {
+                HEigenvalue::Real(v) => {
+                    sum += v;
+                    product *= v;
+                }
+                HEigenvalue::ComplexPair { re, im } => {
+                    sum += 2.0 * re;
+                    product *= re * re + im * im;
+                }
+            }
+        }
+        assert!((sum - 1.5).abs() < 1e-9);
+        assert!((product - a.h_determinant().unwrap()).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_h_svd() {
+        let shapes = [
+            HMatrix::new_from_rows(&[vec![3.0, 0.0], vec![4.0, 5.0]]).unwrap(),
+            HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]).unwrap(),
+            HMatrix::new_from_rows(&[vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]]).unwrap(),
+        ];
+        for a in &shapes {
+            let svd = a.h_svd().unwrap();
+            let us = h_linear_composition(svd.u(), &svd.sigma()).unwrap();
+            assert_matrix_close(&h_linear_composition(&us, svd.vt()).unwrap(), &a.data);
+            let values = svd.singular_values();
+            assert!(values.windows(2).all(|w| w[0] >= w[1]));
+        }
+
+        let svd = shapes[0].h_svd().unwrap();
+        assert!((svd.singular_values()[0] - 45.0f64.sqrt()).abs() < 1e-12);
+        assert!((svd.singular_values()[1] - 5.0f64.sqrt()).abs() < 1e-12);
+
+        let svd = shapes[2].h_svd().unwrap();
+        assert_eq!(svd.h_rank(None), 1);
+        let u_transpose_u = h_dot(&svd.u().get_col(0).unwrap(), &svd.u().get_col(1).unwrap());
+        assert!(u_transpose_u.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_h_pseudo_inverse() {
+        let singular = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.0]]).unwrap();
+        assert_matrix_close(&singular.h_pseudo_inverse().unwrap(), &[0.04, 0.08, 0.08, 0.16]);
+
+        let tall = HMatrix::new_from_rows(&[vec![1.0, 0.0], vec![1.0, 1.0], vec![1.0, 2.0]]).unwrap();
+        let pseudo_inverse = tall.h_pseudo_inverse().unwrap();
+        let identity = h_linear_composition(&pseudo_inverse, &tall).unwrap();
+        assert_matrix_close(&identity, &[1.0, 0.0, 0.0, 1.0]);
+
+        // Least squares through (0, 1), (1, 2), (2, 4) gives y = 0.833... + 1.5x.
+        let coefficients = [1.0, 2.0, 4.0].h_linear_transform(&pseudo_inverse).unwrap();
+        assert!((coefficients[0] - 5.0 / 6.0).abs() < 1e-12);
+        assert!((coefficients[1] - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_h_condition_number_and_numerical_rank() {
+        let diagonal = HMatrix::new_from_rows(&[vec![2.0, 0.0], vec![0.0, 0.5]]).unwrap();
+        assert!((diagonal.h_condition_number().unwrap() - 4.0).abs() < 1e-12);
+
+        let singular = HMatrix::new_from_rows(&[vec![0.0, 0.0], vec![0.0, 0.0]]).unwrap();
+        assert_eq!(singular.h_condition_number(), Some(f64::INFINITY));
+
+        let nearly_singular = HMatrix::new_from_rows(&[vec![1.0, 2.0], vec![2.0, 4.000001]]).unwrap();
+        assert_eq!(nearly_singular.h_numerical_rank(None), 2);
+        assert_eq!(nearly_singular.h_numerical_rank(Some(1e-3)), 1);
+    }
+
+    #[test]
+    fn test_h_transpose() {
+        let matrix = HMatrix::new_from_rows(&[vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        let transposed = matrix.h_transpose();
+        assert_eq!(transposed.shape(), (3, 2));
+        assert_eq!(transposed.data, vec![1, 4, 2, 5, 3, 6]);
+        assert_eq!(HMatrix::new_from_cols(&[vec![1, 4], vec![2, 5], vec![3, 6]]).unwrap(), matrix);
+    }
+
+    #[test]
+    fn test_new_from_cols() {
+        // Three columns of length two make a matrix with two rows and three columns.
+        let matrix = HMatrix::new_from_cols(&[vec![1, 4], vec![2, 5], vec![3, 6]]).unwrap();
+        assert_eq!(matrix.shape(), (2, 3));
+        assert_eq!(matrix.get_row(0), Some(vec![1, 2, 3]));
+        assert_eq!(matrix.get_col(2), Some(vec![3, 6]));
+        assert!(HMatrix::new_from_cols(&[vec![1, 2], vec![3]]).is_none());
+        assert!(HMatrix::<i32>::new_from_cols(&[]).is_none());
+    }
+
+    #[test]
+    fn test_matrix_add_sub_neg() {
+        let a = HMatrix::new_from_rows(&[vec![1, 2], vec![3, 4]]).unwrap();
+        let b = HMatrix::new_from_rows(&[vec![0.5, 0.5], vec![1.0, -1.0]]).unwrap();
+        assert_eq!((&a + &b).data, vec![1.5, 2.5, 4.0, 3.0]);
+        assert_eq!((&a - &b).data, vec![0.5, 1.5, 2.0, 5.0]);
+        assert_eq!((-&a).data, vec![-1.0, -2.0, -3.0, -4.0]);
+        assert_eq!((a + b).data, vec![1.5, 2.5, 4.0, 3.0]);
+    }
+
+    #[test]
+    fn test_matrix_mul() {
+        let a = HMatrix::new_from_rows(&[vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        let b = HMatrix::new_from_rows(&[vec![7.0, 8.0], vec![9.0, 10.0], vec![11.0, 12.0]]).unwrap();
+        let product = &a * &b;
+        assert_eq!(product.shape(), (2, 2));
+        assert_eq!(product.data, vec![58.0, 64.0, 139.0, 154.0]);
+
+        assert_eq!(&a * &vec![1, 0, -1], vec![-2.0, -2.0]);
+        assert_eq!((&a * 2.0).data, vec![2.0, 4.0, 6.0, 8.0, 10.0, 12.0]);
+        assert_eq!((0.5 * &a).data, vec![0.5, 1.0, 1.5, 2.0, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn test_matrix_mul_blocked() {
+        // Sizes that are not multiples of the block size exercise the partial tiles at the edges.
+        let a: HMatrix<f64> = HMatrix::from_fn(70, 130, |i, j| ((i * 7 + j * 3) % 11) as f64 - 5.0);
+        let b: HMatrix<f64> = HMatrix::from_fn(130, 67, |i, j| ((i * 5 + j * 2) % 13) as f64);
+        let product = &a * &b;
+        assert_eq!(product.shape(), (70, 67));
+        for i in 0..70 {
+            for j in 0..67 {
+                let expected: f64 = h_dot(a.row(i).unwrap(), &b.get_col(j).unwrap());
+                assert_eq!(product[(i, j)], expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot multiply a 2x3 matrix by a 2x3 matrix")]
+    fn test_matrix_mul_dimension_mismatch() {
+        let a = HMatrix::new_from_rows(&[vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        let _ = &a * &a;
+    }
+
+    #[test]
+    fn test_matrix_index() {
+        let mut matrix = HMatrix::new_from_rows(&[vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(matrix[(1, 0)], 3);
+        matrix[(0, 1)] = 7;
+        assert_eq!(matrix.get(0, 1), Some(7));
+    }
+
+    #[test]
+    fn test_matrix_constructors() {
+        let zeros: HMatrix<f64> = HMatrix::zeros(2, 3);
+        assert_eq!(zeros.shape(), (2, 3));
+        assert!(zeros.data.iter().all(|x| *x == 0.0));
+
+        let identity: HMatrix<i32> = HMatrix::identity(3);
+        assert_eq!(identity.data, vec![1, 0, 0, 0, 1, 0, 0, 0, 1]);
+
+        let diagonal = HMatrix::from_diagonal(&[2u8, 3u8]);
+        assert_eq!(diagonal.data, vec![2, 0, 0, 3]);
+
+        let from_fn = HMatrix::from_fn(2, 3, |i, j| (10 * i + j) as f64);
+        assert_eq!(from_fn.data, vec![0.0, 1.0, 2.0, 10.0, 11.0, 12.0]);
+
+        let from_flat = HMatrix::from_flat(3, 2, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(from_flat.shape(), (3, 2));
+        assert_eq!(from_flat.get(2, 0), Some(5));
+        assert!(HMatrix::from_flat(2, 2, vec![1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn test_h_matrix_macro() {
+        let matrix = h_matrix![1.0, 2.0, 3.0;
+                               4.0, 5.0, 6.0];
+        assert_eq!(matrix.shape(), (2, 3));
+        assert_eq!(matrix.data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let column = h_matrix![1; 2; 3];
+        assert_eq!(column.shape(), (3, 1));
+        let inverse = h_matrix![2.0, 0.0; 0.0, 4.0].h_inverse().unwrap();
+        assert_eq!(inverse.data, vec![0.5, 0.0, 0.0, 0.25]);
+    }
+
+    #[test]
+    fn test_matrix_display() {
+        let matrix = h_matrix![1.0, -2.5; 30.0, 4.0];
+        assert_eq!(format!("{:.1}", matrix), "[ 1.0 -2.5]\n[30.0  4.0]");
+        assert_eq!(format!("{}", h_matrix![1, 20; 300, 4]), "[  1 20]\n[300  4]");
+    }
+
+    #[test]
+    fn test_matrix_clone_and_eq() {
+        let matrix = h_matrix![1, 2; 3, 4];
+        let mut copy = matrix.clone();
+        assert_eq!(matrix, copy);
+        copy[(0, 0)] = 5;
+        assert_ne!(matrix, copy);
+        assert_eq!(matrix.h_transpose().h_transpose(), matrix);
+    }
+
+    #[test]
+    fn test_h_approx_eq() {
+        let a = h_matrix![4.0, 7.0; 2.0, 6.0];
+        let product = &a * &a.h_inverse().unwrap();
+        assert!(product.h_approx_eq(&HMatrix::<f64>::identity(2), 1e-12));
+        assert!(!a.h_approx_eq(&h_matrix![4.0, 7.0; 2.0, 6.1], 1e-3));
+        assert!(!a.h_approx_eq(&h_matrix![4.0, 7.0, 2.0, 6.0], 1.0));
+    }
+
+    #[test]
+    fn test_matrix_iterators() {
+        let matrix = h_matrix![1, 2, 3; 4, 5, 6];
+        let rows: Vec<&[i32]> = matrix.rows().collect();
+        assert_eq!(rows, vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+        let col_sums: Vec<i32> = matrix.cols().map(|col| col.sum()).collect();
+        assert_eq!(col_sums, vec![5, 7, 9]);
+        assert_eq!(matrix.col(1).unwrap().copied().collect::<Vec<i32>>(), vec![2, 5]);
+        assert!(matrix.col(3).is_none());
+        assert_eq!(matrix.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(matrix.iter().sum::<i32>(), 21);
+        assert_eq!(HMatrix::<f64>::new().rows().count(), 0);
+    }
+
+    #[test]
+    fn test_matrix_in_place_mutation() {
+        let mut matrix = h_matrix![1, 2; 3, 4; 5, 6];
+        matrix.set(0, 1, 9).unwrap();
+        assert!(matrix.set(3, 0, 1).is_err());
+        matrix.swap_rows(0, 2).unwrap();
+        assert_eq!(matrix.data, vec![5, 6, 3, 4, 1, 9]);
+        assert!(matrix.swap_rows(0, 3).is_err());
+
+        for x in matrix.iter_mut() {
+            *x += 1;
+        }
+        matrix.row_mut(1).unwrap()[0] = 0;
+        assert_eq!(matrix.data, vec![6, 7, 0, 5, 2, 10]);
+
+        matrix.map_inplace(|x| x * 2);
+        assert_eq!(matrix.map(|x| x as f64 / 2.0).data, vec![6.0, 7.0, 0.0, 5.0, 2.0, 10.0]);
+    }
+
+    #[test]
+    fn test_matrix_insert_and_remove() {
+        let mut matrix = h_matrix![1, 2, 3; 7, 8, 9];
+        matrix.insert_row(1, vec![4, 5, 6]).unwrap();
+        assert_eq!(matrix, h_matrix![1, 2, 3; 4, 5, 6; 7, 8, 9]);
+        assert!(matrix.insert_row(1, vec![1, 2]).is_err());
+        assert!(matrix.insert_row(5, vec![1, 2, 3]).is_err());
+
+        assert_eq!(matrix.remove_col(1), Some(vec![2, 5, 8]));
+        assert_eq!(matrix, h_matrix![1, 3; 4, 6; 7, 9]);
+        assert_eq!(matrix.remove_row(0), Some(vec![1, 3]));
+        assert_eq!(matrix, h_matrix![4, 6; 7, 9]);
+        assert_eq!(matrix.remove_row(2), None);
+
+        matrix.remove_row(0);
+        matrix.remove_row(0);
+        assert_eq!(matrix.shape(), (0, 0));
+        // An empty row would leave a matrix with one row and no columns.
+        assert!(matrix.insert_row(0, Vec::new()).is_err());
+        assert_eq!(matrix.shape(), (0, 0));
+        matrix.insert_row(0, vec![1, 2]).unwrap();
+        assert_eq!(matrix, h_matrix![1, 2]);
+    }
+
+    #[test]
+    fn test_hvector_operators() {
+        let a = HVector::new(&[1, 2, 3]);
+        let b = HVector::new(&[4.0, 5.0, 6.0]);
+        assert_eq!(&a + &b, HVector::new(&[5.0, 7.0, 9.0]));
+        assert_eq!(b.clone() - a.clone(), HVector::new(&[3.0, 3.0, 3.0]));
+        assert_eq!(&a * &b, 32.0);
+        assert_eq!(2.0 * &a, HVector::new(&[2.0, 4.0, 6.0]));
+        assert_eq!(&b / 2.0, HVector::new(&[2.0, 2.5, 3.0]));
+        assert_eq!(-a.clone(), HVector::new(&[-1, -2, -3]));
+        assert_eq!(&h_matrix![1, 0, 0; 0, 0, 1] * &a, HVector::new(&[1.0, 3.0]));
+
+        // The slice functions and traits keep working through `Deref`.
+        assert_eq!(a[1], 2.0);
+        assert_eq!(h_dot(&a, &b), 32.0);
+        assert_eq!(HVector::new(&[3.0, 4.0]).h_magnitude(), 5.0);
+        assert_eq!(a.h_vector_scalar_mult(2), vec![2.0, 4.0, 6.0]);
+        assert_eq!(format!("{}", a), "[1 2 3]");
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot add a vector of length 3 and a vector of length 2")]
+    fn test_hvector_add_length_mismatch() {
+        let _ = &HVector::new(&[1, 2, 3]) + &HVector::new(&[1, 2]);
+    }
+
+    #[test]
+    fn test_hvector_checked_operations() {
+        let a = HVector::new(&[1, 2, 3]);
+        let mismatch = Err(VectorError::LengthMismatch { left: 3, right: 2 });
+        assert_eq!(a.h_add(&[1, 1]), mismatch);
+        assert_eq!(a.h_sub(&[1, 1]), mismatch);
+        assert_eq!(a.h_hadamard(&[2, 2, 2]), Ok(HVector::new(&[2, 4, 6])));
+        assert_eq!(a.h_dot(&[1.0, 1.0]), Err(VectorError::LengthMismatch { left: 3, right: 2 }));
+        assert_eq!(HVector::new(&[3.0, 4.0]).h_normalize(), Ok(HVector::new(&[0.6, 0.8])));
+        assert_eq!(HVector::zeros(2).h_normalize(), Err(VectorError::ZeroVector));
+    }
+
+    #[test]
+    fn test_hvector_distances() {
+        let a = HVector::new(&[1.0, 2.0]);
+        let b = [4.0, -2.0];
+        assert_eq!(a.h_euclidean_distance(&b), Ok(5.0));
+        assert_eq!(a.h_manhattan_distance(&b), Ok(7.0));
+        assert_eq!(a.h_chebyshev_distance(&b), Ok(4.0));
+        assert_eq!(a.h_chebyshev_distance(&[1.0]), Err(VectorError::LengthMismatch { left: 2, right: 1 }));
+
+        assert_eq!(HVector::new(&[1, 0]).h_cosine_distance(&[0, 5]), Ok(1.0));
+        assert!(a.h_cosine_distance(&[2.0, 4.0]).unwrap().abs() < 1e-12);
+        assert!((a.h_cosine_distance(&[-1.0, -2.0]).unwrap() - 2.0).abs() < 1e-12);
+        assert_eq!(a.h_cosine_distance(&[0.0, 0.0]), Err(VectorError::ZeroVector));
+    }
+
+    #[test]
+    fn test_h_gram_schmidt() {
+        let vectors = vec![vec![3.0, 4.0, 0.0], vec![6.0, 8.0, 0.0], vec![1.0, 0.0, 0.0], vec![1.0, 1.0, 1.0]];
+        let basis = h_gram_schmidt(&vectors).unwrap();
+        assert_eq!(basis.len(), 3);
+        for (i, a) in basis.iter().enumerate() {
+            for (j, b) in basis.iter().enumerate() {
+                let expected: f64 = if i == j { 1.0 } else { 0.0 };
+                assert!((h_dot(a, b) - expected).abs() < 1e-12);
+            }
+        }
+        assert!(h_vector_sub(&basis[0], &[0.6, 0.8, 0.0]).h_magnitude() < 1e-12);
+        assert_eq!(h_gram_schmidt(&[vec![1, 2], vec![1]]), None);
+
+        assert!(h_are_linearly_independent(&[vec![1, 0, 0], vec![1, 1, 0]]));
+        assert!(!h_are_linearly_independent(&[vec![1, 2], vec![2, 4]]));
+        assert!(!h_are_linearly_independent(&[vec![1, 0], vec![0, 0]]));
+
+        let complement = h_orthogonal_complement(&[vec![1.0, 1.0, 0.0], vec![1.0, -1.0, 0.0]]).unwrap();
+        assert_eq!(complement.len(), 1);
+        assert!((complement[0][2].abs() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_matrix_subspaces() {
+        let matrix = h_matrix![1.0, 2.0, 3.0;
+                               2.0, 4.0, 6.0;
+                               1.0, 0.0, 1.0];
+        let column_space = matrix.h_column_space();
+        assert_eq!(column_space.shape(), (3, 2));
+        assert!((&column_space.h_transpose() * &column_space).h_approx_eq(&HMatrix::<f64>::identity(2), 1e-12));
+
+        let null_space = matrix.h_null_space();
+        assert_eq!(null_space.shape(), (3, 1));
+        let image: Vec<f64> = &matrix * &null_space.get_col(0).unwrap();
+        assert!(image.h_magnitude() < 1e-12);
+        assert!((null_space.get_col(0).unwrap().h_magnitude() - 1.0).abs() < 1e-12);
+
+        let wide = h_matrix![1, 0, 1, 0; 0, 1, 0, 1];
+        let null_space = wide.h_null_space();
+        assert_eq!(null_space.shape(), (4, 2));
+        assert!((&wide * &null_space).h_approx_eq(&HMatrix::<f64>::zeros(2, 2), 1e-12));
+
+        assert!(!matrix.h_is_linearly_independent());
+        assert!(h_matrix![1, 0; 1, 1; 0, 1].h_is_linearly_independent());
+        assert_eq!(h_matrix![2, 1; 1, 3].h_null_space().shape(), (2, 0));
+        assert_eq!(h_matrix![1, 2, 3; 0, 1, 4].h_transpose().h_null_space().shape(), (2, 0));
+        assert_eq!(HMatrix::<f64>::zeros(3, 2).h_column_space().shape(), (3, 0));
+    }
+
+    #[test]
+    fn test_complex_matrix() {
+        let i = HComplex::i();
+        let one = HComplex::from(1.0);
+        let matrix = HMatrix::new_from_rows(&[vec![one, i], vec![-i, HComplex::from(2.0)]]).unwrap();
+        assert_eq!(matrix.h_conjugate_transpose(), matrix);
+        assert_eq!(matrix.h_imaginary_part(), h_matrix![0.0, 1.0; -1.0, 0.0]);
+
+        let product = &matrix * &matrix;
+        assert_eq!(product[(0, 0)], HComplex::from(2.0));
+        assert_eq!(product[(0, 1)], HComplex::new(0.0, 3.0));
+        assert_eq!(&(&matrix + &matrix) - &matrix, matrix);
+        assert_eq!((&matrix * i)[(1, 0)], one);
+
+        let real = h_matrix![1, 2; 3, 4];
+        assert_eq!(real.to_complex().h_real_part(), real.map(|x| x as f64));
+        assert_eq!(format!("{}", h_matrix![1.0; 2.0].to_complex()), "[1 + 0i]\n[2 + 0i]");
+    }
+
+    #[test]
+    fn test_h_complex_eigenvalues() {
+        let rotation = h_matrix![0.0, -2.0; 2.0, 0.0];
+        assert_eq!(rotation.h_complex_eigenvalues().unwrap(), vec![HComplex::new(0.0, 2.0), HComplex::new(0.0, -2.0)]);
+        let triangular = h_matrix![3.0, 1.0; 0.0, 1.0];
+        let eigenvalues = triangular.h_complex_eigenvalues().unwrap();
+        assert!(eigenvalues[0].h_approx_eq(&HComplex::from(3.0), 1e-12));
+        assert!(eigenvalues[1].h_approx_eq(&HComplex::from(1.0), 1e-12));
+        assert!(h_matrix![1, 2].h_complex_eigenvalues().is_none());
+    }
+}
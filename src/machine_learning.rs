@@ -107,6 +107,30 @@ impl Perceptron {
         return h_dot(&x_row, &self.weights) + self.bias;
     }
 
+    /// Returns the net input as a symbolic expression for the given number of features, in the variables
+    /// w_0, w_1, ... (weights), x_0, x_1, ... (features) and b (bias): w_0*x_0 + w_1*x_1 + ... + b.
+    /// Combined with a loss, HExpression::h_gradient derives the formulas for training by gradient descent.
+    /// Example usage:
+    /// let net = Perceptron::net_input_expression(2);
+    /// let loss = HExpression::parse(&format!("(y - ({}))^2", net)).unwrap();
+    /// let gradient = loss.h_gradient(&["w_0", "w_1", "b"]);
+    /// gradient[0] is -2*x_0*(y - w_0*x_0 - w_1*x_1 - b), the squared error derivative for weight 0.
+    pub fn net_input_expression(x_features: usize) -> HExpression {
+        let bias: HExpression = HExpression::Variable(String::from("b"));
+        let weighted_sum: Option<HExpression> = (0..x_features)
+            .map(|i| {
+                HExpression::Mul(
+                    Box::new(HExpression::Variable(format!("w_{}", i))),
+                    Box::new(HExpression::Variable(format!("x_{}", i))),
+                )
+            })
+            .reduce(|sum, term| HExpression::Add(Box::new(sum), Box::new(term)));
+        match weighted_sum {
+            Some(sum) => HExpression::Add(Box::new(sum), Box::new(bias)),
+            None => bias,
+        }
+    }
+
     /// Predicts the numeric class (0 or 1) for a given input vector.
     /// Uses the step function: if net_input >= 0, return 1, else 0.
     /// Example usage:
@@ -236,4 +260,25 @@ mod tests {
 
         assert_eq!(&percep.predict_multiple(&x), &y);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn net_input_expression_gradient_matches_hand_derivation() {
+        let net = Perceptron::net_input_expression(2);
+        assert_eq!(net.to_string(), "w_0*x_0 + w_1*x_1 + b");
+
+        // Squared error of the net input: its derivative with respect to w_i is -2*(y - net)*x_i,
+        // which is the direction of the update rule used in `fit`.
+        let loss = HExpression::parse(&format!("(y - ({}))^2", net)).unwrap();
+        let gradient = loss.h_gradient(&["w_0", "w_1", "b"]);
+        assert_eq!(gradient[0].to_string(), "-2*x_0*(y - w_0*x_0 - w_1*x_1 - b)");
+
+        let point = std::collections::HashMap::from([
+            ("w_0", 0.5), ("w_1", -1.0), ("b", 0.25), ("x_0", 2.0), ("x_1", 3.0), ("y", 1.0),
+        ]);
+        let error: f64 = 1.0 - net.h_evaluate(&point).unwrap();
+        let expected = [-2.0 * error * 2.0, -2.0 * error * 3.0, -2.0 * error];
+        for (derivative, expected) in zip(&gradient, expected) {
+            assert!((derivative.h_evaluate(&point).unwrap() - expected).abs() < 1e-12);
+        }
+    }
+}
@@ -1,11 +1,16 @@
 pub use crate::algebra::*;
+pub use crate::complex::*;
 pub use crate::conversions::*;
 pub use crate::core_math::*;
+pub use crate::expression::*;
 pub use crate::finance::*;
+pub use crate::fixed_matrix::*;
 pub use crate::functionality::*;
 pub use crate::geometry::*;
 pub use crate::linear_algebra::*;
 pub use crate::probability::*;
+pub use crate::sparse_matrix::*;
 pub use crate::statistics::*;
 pub use crate::terminal_input::*;
+pub use crate::transforms::*;
 pub use crate::machine_learning::*;
\ No newline at end of file
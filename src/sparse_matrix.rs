@@ -0,0 +1,549 @@
+use crate::prelude::*;
+use std::ops::Mul;
+
+
+/// A sparse matrix in coordinate (COO) format, which is a list of `(row, column, value)` triplets.
+/// It is the easiest format to build a sparse matrix in, since entries can be pushed in any order.
+/// Entries pushed more than once for the same position are summed when converting,
+/// which is what finite-element and finite-difference assembly expects.
+/// Convert it with `to_csr` or `to_csc` before doing arithmetic with it.
+///
+/// Example:
+///
+/// let mut builder = HCooMatrix::new(3, 3);
+/// builder.push(0, 0, 2.0).unwrap();
+/// builder.push(1, 2, -1.0).unwrap();
+/// let matrix = builder.to_csr();
+pub struct HCooMatrix {
+    rows: usize,
+    cols: usize,
+    entries: Vec<(usize, usize, f64)>,
+}
+
+impl HCooMatrix {
+    /// Creates an empty sparse matrix with the given dimensions, where every element is zero.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        HCooMatrix {
+            rows,
+            cols,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds `value` to the element at `(row, col)`. Returns an error if the position is out of bounds.
+    pub fn push<T>(&mut self, row: usize, col: usize, value: T) -> Result<(), String>
+    where
+        T: Copy + Into<f64>,
+    {
+        if row >= self.rows || col >= self.cols {
+            return Err(format!(
+                "index ({}, {}) is out of bounds for a {}x{} matrix. from: HCooMatrix, push()",
+                row, col, self.rows, self.cols
+            ));
+        }
+        self.entries.push((row, col, value.into()));
+        Ok(())
+    }
+
+    /// Returns the dimensions of the matrix as `(rows, columns)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Converts the triplets into compressed sparse row (CSR) format, summing duplicates and dropping zeros.
+    pub fn to_csr(&self) -> HCsrMatrix {
+        let (offsets, indices, values) = compress(self.rows, self.entries.iter().copied());
+        HCsrMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            row_offsets: offsets,
+            col_indices: indices,
+            values,
+        }
+    }
+
+    /// Converts the triplets into compressed sparse column (CSC) format, summing duplicates and dropping zeros.
+    pub fn to_csc(&self) -> HCscMatrix {
+        let (offsets, indices, values) = compress(self.cols, self.entries.iter().map(|(r, c, v)| (*c, *r, *v)));
+        HCscMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            col_offsets: offsets,
+            row_indices: indices,
+            values,
+        }
+    }
+}
+
+
+/// Compresses `(major, minor, value)` triplets into offsets, indices and values, where the entries of
+/// major index `i` are at `offsets[i]..offsets[i + 1]` sorted by minor index.
+/// Duplicate positions are summed, and entries that end up as zero are dropped.
+fn compress<I>(major_len: usize, triplets: I) -> (Vec<usize>, Vec<usize>, Vec<f64>)
+where
+    I: Iterator<Item = (usize, usize, f64)>,
+{
+    let mut sorted: Vec<(usize, usize, f64)> = triplets.collect();
+    sorted.sort_by_key(|(major, minor, _)| (*major, *minor));
+
+    let mut offsets: Vec<usize> = vec![0; major_len + 1];
+    let mut indices: Vec<usize> = Vec::with_capacity(sorted.len());
+    let mut values: Vec<f64> = Vec::with_capacity(sorted.len());
+    let mut majors: Vec<usize> = Vec::with_capacity(sorted.len());
+
+    for (major, minor, value) in sorted {
+        if majors.last() == Some(&major) && indices.last() == Some(&minor) {
+            *values.last_mut().unwrap() += value;
+        } else {
+            majors.push(major);
+            indices.push(minor);
+            values.push(value);
+        }
+    }
+
+    let mut kept: usize = 0;
+    for i in 0..values.len() {
+        if values[i] != 0.0 {
+            majors[kept] = majors[i];
+            indices[kept] = indices[i];
+            values[kept] = values[i];
+            kept += 1;
+        }
+    }
+    majors.truncate(kept);
+    indices.truncate(kept);
+    values.truncate(kept);
+
+    for major in &majors {
+        offsets[major + 1] += 1;
+    }
+    for i in 0..major_len {
+        offsets[i + 1] += offsets[i];
+    }
+    (offsets, indices, values)
+}
+
+
+/// A sparse matrix in compressed sparse row (CSR) format. Only the non-zero elements are stored,
+/// row by row, so a matrix with millions of rows but only a few entries per row fits in memory.
+/// CSR is the format of choice for matrix-vector products and iterative solvers.
+/// Build it with `HCooMatrix`, or convert a dense `HMatrix` with `from_dense`.
+pub struct HCsrMatrix {
+    rows: usize,
+    cols: usize,
+    row_offsets: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl HCsrMatrix {
+    /// Creates a sparse matrix from a dense `HMatrix`, keeping only the non-zero elements.
+    pub fn from_dense<T>(matrix: &HMatrix<T>) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        let (rows, cols) = matrix.shape();
+        let mut row_offsets: Vec<usize> = Vec::with_capacity(rows + 1);
+        let mut col_indices: Vec<usize> = Vec::new();
+        let mut values: Vec<f64> = Vec::new();
+        row_offsets.push(0);
+        for row in matrix.rows() {
+            for (c, value) in row.iter().enumerate() {
+                let value: f64 = (*value).into();
+                if value != 0.0 {
+                    col_indices.push(c);
+                    values.push(value);
+                }
+            }
+            row_offsets.push(values.len());
+        }
+        HCsrMatrix {
+            rows,
+            cols,
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+
+    /// Converts the sparse matrix into a dense `HMatrix`.
+    pub fn to_dense(&self) -> HMatrix<f64> {
+        let mut data: Vec<f64> = vec![0.0; self.rows * self.cols];
+        for (r, c, value) in self.iter() {
+            data[r * self.cols + c] = value;
+        }
+        HMatrix::from_flat(self.rows, self.cols, data).unwrap()
+    }
+
+    /// Converts the matrix into compressed sparse column (CSC) format.
+    pub fn to_csc(&self) -> HCscMatrix {
+        let (col_offsets, row_indices, values) = compress(self.cols, self.iter().map(|(r, c, v)| (c, r, v)));
+        HCscMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            col_offsets,
+            row_indices,
+            values,
+        }
+    }
+
+    /// Returns the dimensions of the matrix as `(rows, columns)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Returns the number of stored (non-zero) elements.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Retrieves the value at the specified row and column indices, which is `0.0` for elements that are not stored.
+    /// If the indices are out of bounds, the function returns `None`.
+    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        let start: usize = self.row_offsets[row];
+        let end: usize = self.row_offsets[row + 1];
+        match self.col_indices[start..end].binary_search(&col) {
+            Ok(i) => Some(self.values[start + i]),
+            Err(_) => Some(0.0),
+        }
+    }
+
+    /// Returns an iterator over the stored elements as `(row, column, value)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.rows).flat_map(move |r| {
+            (self.row_offsets[r]..self.row_offsets[r + 1]).map(move |i| (r, self.col_indices[i], self.values[i]))
+        })
+    }
+
+    /// Returns the transpose of the matrix.
+    pub fn h_transpose(&self) -> HCsrMatrix {
+        let (row_offsets, col_indices, values) = compress(self.cols, self.iter().map(|(r, c, v)| (c, r, v)));
+        HCsrMatrix {
+            rows: self.cols,
+            cols: self.rows,
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+
+    /// Multiplies the sparse matrix by a dense vector. Only the stored elements are visited,
+    /// so the cost is proportional to `nnz()` instead of `rows * cols`.
+    /// If the vector length does not equal the number of columns, the function returns `None`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HCsrMatrix::from_dense(&h_matrix![2.0, 0.0; 0.0, 3.0]);
+    /// assert_eq!(matrix.h_mul_vector(&[1.0, 2.0]), Some(vec![2.0, 6.0]));
+    pub fn h_mul_vector<S>(&self, vector: &[S]) -> Option<Vec<f64>>
+    where
+        S: Copy + Into<f64>,
+    {
+        if vector.len() != self.cols {
+            return None;
+        }
+        let mut result: Vec<f64> = vec![0.0; self.rows];
+        self.mul_vector_into(vector, &mut result);
+        Some(result)
+    }
+
+    /// Writes the product of the matrix and `vector` into `out`, which must have one entry per row.
+    /// Iterative solvers use this to reuse the same buffer in every iteration.
+    fn mul_vector_into<S>(&self, vector: &[S], out: &mut [f64])
+    where
+        S: Copy + Into<f64>,
+    {
+        for (r, value) in out.iter_mut().enumerate() {
+            let start: usize = self.row_offsets[r];
+            let end: usize = self.row_offsets[r + 1];
+            *value = (start..end).map(|i| self.values[i] * vector[self.col_indices[i]].into()).sum();
+        }
+    }
+
+    /// Solves `Ax = b` with the conjugate gradient method, for a symmetric positive-definite matrix `A`.
+    /// Every iteration only needs one sparse matrix-vector product, which makes it the standard solver for
+    /// large sparse systems, such as discretized Poisson equations and graph Laplacians, where elimination
+    /// would fill in the zeros. The iteration starts at `x = 0` and stops when `||b - Ax|| <= tolerance * ||b||`.
+    ///
+    /// Returns `LinearSystemError::NotSquare` if the matrix is not square, `LinearSystemError::DimensionMismatch`
+    /// if `rhs` has the wrong length,
+    /// `LinearSystemError::NotPositiveDefinite` if the iteration finds a direction with `pᵀAp <= 0`,
+    /// and `LinearSystemError::NotConverged` if the tolerance is not reached within `max_iterations`.
+    ///
+    /// Example:
+    ///
+    /// let matrix = HCsrMatrix::from_dense(&h_matrix![4.0, 1.0; 1.0, 3.0]);
+    /// let x = matrix.h_conjugate_gradient(&[1.0, 2.0], 1e-10, 100).unwrap();
+    /// x is approximately [0.0909, 0.6364], the exact solution [1/11, 7/11].
+    pub fn h_conjugate_gradient<S>(&self, rhs: &[S], tolerance: f64, max_iterations: usize) -> Result<Vec<f64>, LinearSystemError>
+    where
+        S: Copy + Into<f64>,
+    {
+        if self.rows != self.cols {
+            return Err(LinearSystemError::NotSquare {
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        if rhs.len() != self.rows {
+            return Err(LinearSystemError::DimensionMismatch {
+                equations: self.rows,
+                rhs_len: rhs.len(),
+            });
+        }
+        let b: Vec<f64> = rhs.iter().map(|x| (*x).into()).collect();
+        let target: f64 = tolerance * b.h_magnitude();
+        let mut x: Vec<f64> = vec![0.0; self.rows];
+        let mut residual: Vec<f64> = b;
+        let mut direction: Vec<f64> = residual.clone();
+        let mut a_direction: Vec<f64> = vec![0.0; self.rows];
+        let mut residual_squared: f64 = h_dot(&residual, &residual);
+
+        for iteration in 0..=max_iterations {
+            if residual_squared.sqrt() <= target {
+                return Ok(x);
+            }
+            if iteration == max_iterations {
+                break;
+            }
+            // The vectors are updated in place, so an iteration allocates nothing.
+            self.mul_vector_into(&direction, &mut a_direction);
+            let curvature: f64 = h_dot(&direction, &a_direction);
+            if curvature <= 0.0 {
+                return Err(LinearSystemError::NotPositiveDefinite);
+            }
+            let alpha: f64 = residual_squared / curvature;
+            for i in 0..self.rows {
+                x[i] += alpha * direction[i];
+                residual[i] -= alpha * a_direction[i];
+            }
+            let new_residual_squared: f64 = h_dot(&residual, &residual);
+            let beta: f64 = new_residual_squared / residual_squared;
+            for (d, r) in direction.iter_mut().zip(residual.iter()) {
+                *d = r + beta * *d;
+            }
+            residual_squared = new_residual_squared;
+        }
+        Err(LinearSystemError::NotConverged { iterations: max_iterations })
+    }
+}
+
+impl<S> Mul<&[S]> for &HCsrMatrix
+where
+    S: Copy + Into<f64>,
+{
+    type Output = Vec<f64>;
+
+    /// Multiplies the sparse matrix by a dense vector. Panics if the vector length does not equal the number of columns.
+    fn mul(self, vector: &[S]) -> Vec<f64> {
+        match self.h_mul_vector(vector) {
+            Some(result) => result,
+            None => panic!(
+                "from: HCsrMatrix Mul, cannot multiply a {}x{} matrix by a vector of length {}",
+                self.rows, self.cols, vector.len()
+            ),
+        }
+    }
+}
+
+impl<S> Mul<&Vec<S>> for &HCsrMatrix
+where
+    S: Copy + Into<f64>,
+{
+    type Output = Vec<f64>;
+
+    fn mul(self, vector: &Vec<S>) -> Vec<f64> {
+        self * vector.as_slice()
+    }
+}
+
+
+/// A sparse matrix in compressed sparse column (CSC) format. Only the non-zero elements are stored,
+/// column by column, which makes column access cheap and is the natural format for `Aᵀx` products.
+/// Build it with `HCooMatrix`, or convert a dense `HMatrix` with `from_dense`.
+pub struct HCscMatrix {
+    rows: usize,
+    cols: usize,
+    col_offsets: Vec<usize>,
+    row_indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl HCscMatrix {
+    /// Creates a sparse matrix from a dense `HMatrix`, keeping only the non-zero elements.
+    pub fn from_dense<T>(matrix: &HMatrix<T>) -> Self
+    where
+        T: Copy + Into<f64>,
+    {
+        HCsrMatrix::from_dense(matrix).to_csc()
+    }
+
+    /// Converts the sparse matrix into a dense `HMatrix`.
+    pub fn to_dense(&self) -> HMatrix<f64> {
+        let mut data: Vec<f64> = vec![0.0; self.rows * self.cols];
+        for (r, c, value) in self.iter() {
+            data[r * self.cols + c] = value;
+        }
+        HMatrix::from_flat(self.rows, self.cols, data).unwrap()
+    }
+
+    /// Converts the matrix into compressed sparse row (CSR) format.
+    pub fn to_csr(&self) -> HCsrMatrix {
+        let (row_offsets, col_indices, values) = compress(self.rows, self.iter());
+        HCsrMatrix {
+            rows: self.rows,
+            cols: self.cols,
+            row_offsets,
+            col_indices,
+            values,
+        }
+    }
+
+    /// Returns the dimensions of the matrix as `(rows, columns)`.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// Returns the number of stored (non-zero) elements.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Retrieves the value at the specified row and column indices, which is `0.0` for elements that are not stored.
+    /// If the indices are out of bounds, the function returns `None`.
+    pub fn get(&self, row: usize, col: usize) -> Option<f64> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        let start: usize = self.col_offsets[col];
+        let end: usize = self.col_offsets[col + 1];
+        match self.row_indices[start..end].binary_search(&row) {
+            Ok(i) => Some(self.values[start + i]),
+            Err(_) => Some(0.0),
+        }
+    }
+
+    /// Returns an iterator over the stored elements as `(row, column, value)`, column by column.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        (0..self.cols).flat_map(move |c| {
+            (self.col_offsets[c]..self.col_offsets[c + 1]).map(move |i| (self.row_indices[i], c, self.values[i]))
+        })
+    }
+
+    /// Multiplies the sparse matrix by a dense vector, by adding every column scaled by the matching vector element.
+    /// If the vector length does not equal the number of columns, the function returns `None`.
+    pub fn h_mul_vector<S>(&self, vector: &[S]) -> Option<Vec<f64>>
+    where
+        S: Copy + Into<f64>,
+    {
+        if vector.len() != self.cols {
+            return None;
+        }
+        let mut result: Vec<f64> = vec![0.0; self.rows];
+        for (c, x) in vector.iter().enumerate() {
+            let x: f64 = (*x).into();
+            if x == 0.0 {
+                continue;
+            }
+            for i in self.col_offsets[c]..self.col_offsets[c + 1] {
+                result[self.row_indices[i]] += self.values[i] * x;
+            }
+        }
+        Some(result)
+    }
+}
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::h_matrix;
+
+    #[test]
+    fn test_coo_to_csr_and_csc() {
+        let mut builder = HCooMatrix::new(3, 4);
+        builder.push(2, 1, 5.0).unwrap();
+        builder.push(0, 3, 1).unwrap();
+        builder.push(0, 0, 2.0).unwrap();
+        builder.push(2, 1, 1.0).unwrap();
+        builder.push(1, 2, 0.0).unwrap();
+        assert!(builder.push(3, 0, 1.0).is_err());
+
+        let csr = builder.to_csr();
+        assert_eq!(csr.nnz(), 3);
+        assert_eq!(csr.get(2, 1), Some(6.0));
+        assert_eq!(csr.get(1, 2), Some(0.0));
+        assert_eq!(csr.get(3, 0), None);
+        let expected = h_matrix![2.0, 0.0, 0.0, 1.0; 0.0, 0.0, 0.0, 0.0; 0.0, 6.0, 0.0, 0.0];
+        assert_eq!(csr.to_dense(), expected);
+
+        let csc = builder.to_csc();
+        assert_eq!(csc.to_dense(), expected);
+        assert_eq!(csc.to_csr().to_dense(), expected);
+        assert_eq!(csr.to_csc().get(0, 3), Some(1.0));
+    }
+
+    #[test]
+    fn test_sparse_dense_conversion() {
+        let dense = h_matrix![1, 0, 0; 0, 0, 3; 4, 0, 5];
+        let csr = HCsrMatrix::from_dense(&dense);
+        assert_eq!(csr.nnz(), 4);
+        assert_eq!(csr.iter().collect::<Vec<_>>(), vec![(0, 0, 1.0), (1, 2, 3.0), (2, 0, 4.0), (2, 2, 5.0)]);
+        assert_eq!(csr.h_transpose().to_dense(), HCsrMatrix::from_dense(&dense.h_transpose()).to_dense());
+        assert_eq!(HCscMatrix::from_dense(&dense).to_dense(), csr.to_dense());
+    }
+
+    #[test]
+    fn test_sparse_mul_vector() {
+        let dense = h_matrix![1.0, 0.0, 2.0; 0.0, 3.0, 0.0];
+        let x = [1.0, 2.0, 3.0];
+        let expected = &dense * &x[..];
+        assert_eq!(HCsrMatrix::from_dense(&dense).h_mul_vector(&x), Some(expected.clone()));
+        assert_eq!(HCscMatrix::from_dense(&dense).h_mul_vector(&x), Some(expected.clone()));
+        assert_eq!(&HCsrMatrix::from_dense(&dense) * &x[..], expected);
+        assert_eq!(HCsrMatrix::from_dense(&dense).h_mul_vector(&[1.0]), None);
+    }
+
+    #[test]
+    fn test_conjugate_gradient() {
+        // The 1D Poisson matrix tridiag(-1, 2, -1) is symmetric positive-definite.
+        let n: usize = 200;
+        let mut builder = HCooMatrix::new(n, n);
+        for i in 0..n {
+            builder.push(i, i, 2.0).unwrap();
+            if i > 0 {
+                builder.push(i, i - 1, -1.0).unwrap();
+                builder.push(i - 1, i, -1.0).unwrap();
+            }
+        }
+        let matrix = builder.to_csr();
+        assert_eq!(matrix.nnz(), 3 * n - 2);
+
+        let b: Vec<f64> = vec![1.0; n];
+        let x = matrix.h_conjugate_gradient(&b, 1e-10, 1000).unwrap();
+        let residual = h_vector_sub(&matrix.h_mul_vector(&x).unwrap(), &b);
+        assert!(residual.h_magnitude() <= 1e-10 * b.h_magnitude());
+        assert!(matches!(
+            matrix.h_conjugate_gradient(&b, 1e-10, 3),
+            Err(LinearSystemError::NotConverged { iterations: 3 })
+        ));
+
+        let indefinite = HCsrMatrix::from_dense(&h_matrix![1.0, 0.0; 0.0, -1.0]);
+        assert_eq!(indefinite.h_conjugate_gradient(&[1.0, 1.0], 1e-10, 10), Err(LinearSystemError::NotPositiveDefinite));
+
+        let rectangular = HCsrMatrix::from_dense(&h_matrix![1.0, 0.0, 0.0; 0.0, 1.0, 0.0]);
+        assert_eq!(
+            rectangular.h_conjugate_gradient(&[1.0, 1.0], 1e-10, 10),
+            Err(LinearSystemError::NotSquare { rows: 2, cols: 3 })
+        );
+        assert_eq!(
+            matrix.h_conjugate_gradient(&[1.0, 1.0], 1e-10, 10),
+            Err(LinearSystemError::DimensionMismatch { equations: n, rhs_len: 2 })
+        );
+    }
+}
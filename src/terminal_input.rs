@@ -3,6 +3,8 @@ use std::io;
 use std::io::Write;
 use std::collections::HashSet;
 
+use crate::expression::HExpression;
+
 // -------------------------------- Input Terminal ------------------------------
 /// Collects a vector of `f64` values from user input via stdin.
 ///
@@ -124,6 +126,49 @@ pub fn h_input_data_single_i32(length: Option<u32>) -> Vec<i32> {
     }
 }
 
+/// Reads a formula from stdin and parses it into an `HExpression`, asking again until it parses.
+///
+/// # Arguments
+///
+/// * `prompt` - Text printed before each attempt, such as `"f(x) = "`.
+///
+/// # Behavior
+///
+/// - Prints the prompt and reads one line.
+/// - If the line does not parse, echoes it with a `^` under the position of the problem,
+///   prints the `ParseError`, and prompts again.
+/// - Returns the first expression that parses.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let expression = h_input_expression("f(x) = ");
+/// let value = expression.h_evaluate(&HashMap::from([("x", 2.0)]));
+/// ```
+pub fn h_input_expression(prompt: &str) -> HExpression {
+    loop {
+        let mut input: String = String::new();
+
+        print!("{}", prompt);
+        io::stdout().flush().unwrap();
+        if io::stdin()
+        .read_line(&mut input)
+        .is_err() {
+            println!("Failed to read line");
+            continue;
+        }
+        let formula: &str = input.trim_end();
+        match HExpression::parse(formula) {
+            Ok(expression) => return expression,
+            Err(error) => {
+                println!("{}", formula);
+                println!("{}^ {:?}", " ".repeat(error.position()), error);
+            }
+        }
+    }
+}
+
+
 
 #[derive(PartialEq, Eq, Debug)]
 /// Represents the type of input validation to perform.
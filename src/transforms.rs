@@ -0,0 +1,499 @@
+use crate::prelude::*;
+use std::ops::{Mul, Neg};
+
+
+/// Converts an angle in the given unit to radians.
+fn to_radians(angle: f64, measurement: Measurement) -> f64 {
+    match measurement {
+        Measurement::Radians => angle,
+        Measurement::Degrees => angle.to_radians(),
+    }
+}
+
+/// Converts a 3-component axis into a unit vector. If the axis does not have three components
+/// or is the zero vector, the function returns `None`.
+fn unit_axis<T>(axis: &[T]) -> Option<[f64; 3]>
+where
+    T: Copy + Into<f64>,
+{
+    let magnitude: f64 = axis.h_magnitude();
+    if axis.len() != 3 || magnitude == 0.0 {
+        return None;
+    }
+    Some([axis[0].into() / magnitude, axis[1].into() / magnitude, axis[2].into() / magnitude])
+}
+
+
+/// Creates the 3x3 matrix that rotates vectors by `angle` around `axis`, counterclockwise when
+/// looking down the axis towards the origin (the right-hand rule). The axis does not need to be unit length.
+/// The matrix is built with Rodrigues' formula `R = cos(θ)I + sin(θ)[k]× + (1 - cos(θ))kkᵀ`.
+/// If the axis does not have three components or is the zero vector, the function returns `None`.
+///
+/// Example:
+///
+/// let rotation = h_rotation_from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap();
+/// let rotated = &rotation * &vec![1.0, 0.0, 0.0];
+/// rotated is approximately [0.0, 1.0, 0.0]
+pub fn h_rotation_from_axis_angle<T>(axis: &[T], angle: f64, measurement: Measurement) -> Option<HMatrix<f64>>
+where
+    T: Copy + Into<f64>,
+{
+    let [x, y, z] = unit_axis(axis)?;
+    let angle: f64 = to_radians(angle, measurement);
+    let (sin, cos) = angle.sin_cos();
+    let t: f64 = 1.0 - cos;
+    HMatrix::from_flat(3, 3, vec![
+        cos + x * x * t,     x * y * t - z * sin, x * z * t + y * sin,
+        y * x * t + z * sin, cos + y * y * t,     y * z * t - x * sin,
+        z * x * t - y * sin, z * y * t + x * sin, cos + z * z * t,
+    ])
+}
+
+/// Creates the 3x3 rotation matrix for the Euler angles `roll` (around x), `pitch` (around y) and `yaw` (around z).
+/// The rotations are applied to a vector in the order roll, pitch, yaw, so the matrix is `R = Rz(yaw) Ry(pitch) Rx(roll)`.
+/// This is the yaw-pitch-roll convention used for aircraft and most 3D engines.
+///
+/// Example:
+///
+/// let rotation = h_rotation_from_euler(0.0, 0.0, 90.0, Measurement::Degrees);
+/// rotation is approximately [[0, -1, 0], [1, 0, 0], [0, 0, 1]], the same as a 90° rotation around z.
+pub fn h_rotation_from_euler(roll: f64, pitch: f64, yaw: f64, measurement: Measurement) -> HMatrix<f64> {
+    HQuaternion::from_euler(roll, pitch, yaw, measurement).to_rotation_matrix()
+}
+
+
+/// A quaternion `w + xi + yj + zk`. Unit quaternions represent 3D rotations without the gimbal lock of
+/// Euler angles, compose with a single multiplication, and can be interpolated smoothly with `h_slerp`.
+/// The product `a * b` is the rotation that applies `b` first and then `a`, the same order as matrix multiplication.
+///
+/// Example:
+///
+/// let quarter_turn = HQuaternion::from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap();
+/// let rotated = quarter_turn.h_rotate_vector(&[1.0, 0.0, 0.0]).unwrap();
+/// rotated is approximately [0.0, 1.0, 0.0]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HQuaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl HQuaternion {
+    /// Creates the quaternion `w + xi + yj + zk`.
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        HQuaternion { w, x, y, z }
+    }
+
+    /// Creates the identity quaternion `1`, which represents no rotation.
+    pub fn identity() -> Self {
+        HQuaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Creates the unit quaternion that rotates by `angle` around `axis` (see `h_rotation_from_axis_angle`).
+    /// If the axis does not have three components or is the zero vector, the function returns `None`.
+    pub fn from_axis_angle<T>(axis: &[T], angle: f64, measurement: Measurement) -> Option<Self>
+    where
+        T: Copy + Into<f64>,
+    {
+        let [x, y, z] = unit_axis(axis)?;
+        let (sin, cos) = (to_radians(angle, measurement) / 2.0).sin_cos();
+        Some(HQuaternion::new(cos, x * sin, y * sin, z * sin))
+    }
+
+    /// Creates the unit quaternion for the Euler angles `roll`, `pitch` and `yaw` (see `h_rotation_from_euler`).
+    pub fn from_euler(roll: f64, pitch: f64, yaw: f64, measurement: Measurement) -> Self {
+        let (sr, cr) = (to_radians(roll, measurement) / 2.0).sin_cos();
+        let (sp, cp) = (to_radians(pitch, measurement) / 2.0).sin_cos();
+        let (sy, cy) = (to_radians(yaw, measurement) / 2.0).sin_cos();
+        HQuaternion::new(
+            cr * cp * cy + sr * sp * sy,
+            sr * cp * cy - cr * sp * sy,
+            cr * sp * cy + sr * cp * sy,
+            cr * cp * sy - sr * sp * cy,
+        )
+    }
+
+    /// Returns the real part `w`.
+    pub fn w(&self) -> f64 {
+        self.w
+    }
+
+    /// Returns the `i` component `x`.
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    /// Returns the `j` component `y`.
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    /// Returns the `k` component `z`.
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    /// Calculates the four-dimensional dot product with another quaternion.
+    pub fn h_dot(&self, other: &HQuaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Calculates the magnitude `sqrt(w² + x² + y² + z²)`.
+    pub fn h_magnitude(&self) -> f64 {
+        self.h_dot(self).sqrt()
+    }
+
+    /// Returns the quaternion scaled to unit magnitude. Returns `VectorError::ZeroVector` if the quaternion is zero.
+    pub fn h_normalize(&self) -> Result<HQuaternion, VectorError> {
+        let magnitude: f64 = self.h_magnitude();
+        if magnitude == 0.0 {
+            return Err(VectorError::ZeroVector);
+        }
+        Ok(HQuaternion::new(self.w / magnitude, self.x / magnitude, self.y / magnitude, self.z / magnitude))
+    }
+
+    /// Returns the conjugate `w - xi - yj - zk`, which is the inverse rotation for a unit quaternion.
+    pub fn h_conjugate(&self) -> HQuaternion {
+        HQuaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    /// Calculates the multiplicative inverse `q* / |q|²`. If the quaternion is zero, the function returns `None`.
+    pub fn h_inverse(&self) -> Option<HQuaternion> {
+        let norm_squared: f64 = self.h_dot(self);
+        if norm_squared == 0.0 {
+            return None;
+        }
+        let conjugate: HQuaternion = self.h_conjugate();
+        Some(HQuaternion::new(
+            conjugate.w / norm_squared,
+            conjugate.x / norm_squared,
+            conjugate.y / norm_squared,
+            conjugate.z / norm_squared,
+        ))
+    }
+
+    /// Rotates a 3D vector by the rotation the quaternion represents, computed as `q v q*` for the normalized quaternion.
+    /// If the vector does not have three components or the quaternion is zero, the function returns `None`.
+    pub fn h_rotate_vector<T>(&self, vector: &[T]) -> Option<Vec<f64>>
+    where
+        T: Copy + Into<f64>,
+    {
+        if vector.len() != 3 {
+            return None;
+        }
+        let q: HQuaternion = self.h_normalize().ok()?;
+        let v: HQuaternion = HQuaternion::new(0.0, vector[0].into(), vector[1].into(), vector[2].into());
+        let rotated: HQuaternion = q * v * q.h_conjugate();
+        Some(vec![rotated.x, rotated.y, rotated.z])
+    }
+
+    /// Converts the quaternion into the equivalent 3x3 rotation matrix. The quaternion is normalized first,
+    /// and the zero quaternion gives the identity matrix.
+    pub fn to_rotation_matrix(&self) -> HMatrix<f64> {
+        let HQuaternion { w, x, y, z } = self.h_normalize().unwrap_or(HQuaternion::identity());
+        HMatrix::from_flat(3, 3, vec![
+            1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w),       2.0 * (x * z + y * w),
+            2.0 * (x * y + z * w),       1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w),
+            2.0 * (x * z - y * w),       2.0 * (y * z + x * w),       1.0 - 2.0 * (x * x + y * y),
+        ])
+        .unwrap()
+    }
+
+    /// Spherical linear interpolation between two rotations, where `t = 0` gives `self` and `t = 1` gives `other`.
+    /// Unlike interpolating the matrices or Euler angles, the rotation turns at a constant angular speed.
+    /// The interpolation takes the shorter way around, and falls back to normalized linear interpolation
+    /// when the rotations are almost identical, where the slerp formula would divide by a tiny `sin(θ)`.
+    /// Both quaternions are normalized first; the zero quaternion is treated as the identity.
+    ///
+    /// Example:
+    ///
+    /// let start = HQuaternion::identity();
+    /// let end = HQuaternion::from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap();
+    /// let halfway = start.h_slerp(&end, 0.5);
+    /// halfway is the 45° rotation around z.
+    pub fn h_slerp(&self, other: &HQuaternion, t: f64) -> HQuaternion {
+        let a: HQuaternion = self.h_normalize().unwrap_or(HQuaternion::identity());
+        let mut b: HQuaternion = other.h_normalize().unwrap_or(HQuaternion::identity());
+        let mut cosine: f64 = a.h_dot(&b);
+        // q and -q are the same rotation, so flip one to take the shorter arc.
+        if cosine < 0.0 {
+            b = -b;
+            cosine = -cosine;
+        }
+        let (weight_a, weight_b) = if cosine > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta: f64 = cosine.clamp(-1.0, 1.0).acos();
+            let sin_theta: f64 = theta.sin();
+            (((1.0 - t) * theta).sin() / sin_theta, (t * theta).sin() / sin_theta)
+        };
+        let result: HQuaternion = HQuaternion::new(
+            weight_a * a.w + weight_b * b.w,
+            weight_a * a.x + weight_b * b.x,
+            weight_a * a.y + weight_b * b.y,
+            weight_a * a.z + weight_b * b.z,
+        );
+        result.h_normalize().unwrap_or(a)
+    }
+}
+
+impl Mul for HQuaternion {
+    type Output = HQuaternion;
+
+    /// The Hamilton product, which composes rotations: `(a * b)` applies `b` first and then `a`.
+    fn mul(self, other: HQuaternion) -> HQuaternion {
+        HQuaternion::new(
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        )
+    }
+}
+
+impl Neg for HQuaternion {
+    type Output = HQuaternion;
+
+    fn neg(self) -> HQuaternion {
+        HQuaternion::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+
+/// A 3D affine transform (a linear map followed by a translation), stored as a 4x4 homogeneous `HMatrix`
+/// whose last row is `[0, 0, 0, 1]`. Points are transformed as `[x, y, z, 1]` and are affected by the translation,
+/// while direction vectors are transformed as `[x, y, z, 0]` and are not.
+/// Transforms are combined with `then` or `*`, where `a * b` applies `b` first and then `a`.
+///
+/// Example:
+///
+/// let transform = HAffineTransform::scaling(2.0, 2.0, 2.0).then(&HAffineTransform::translation(1.0, 0.0, 0.0));
+/// assert_eq!(transform.h_transform_point(&[1.0, 1.0, 1.0]), Some(vec![3.0, 2.0, 2.0]));
+#[derive(Debug, Clone, PartialEq)]
+pub struct HAffineTransform {
+    matrix: HMatrix<f64>,
+}
+
+impl HAffineTransform {
+    /// Creates a transform from a 3x3 linear part and a translation.
+    fn from_parts(linear: &HMatrix<f64>, translation: [f64; 3]) -> Self {
+        HAffineTransform {
+            matrix: HMatrix::from_fn(4, 4, |r, c| match (r, c) {
+                (3, 3) => 1.0,
+                (3, _) => 0.0,
+                (_, 3) => translation[r],
+                _ => linear[(r, c)],
+            }),
+        }
+    }
+
+    /// Creates the identity transform, which leaves every point where it is.
+    pub fn identity() -> Self {
+        HAffineTransform::scaling(1.0, 1.0, 1.0)
+    }
+
+    /// Creates a transform that moves every point by `(x, y, z)`.
+    pub fn translation(x: f64, y: f64, z: f64) -> Self {
+        HAffineTransform::from_parts(&HMatrix::from_diagonal(&[1.0, 1.0, 1.0]), [x, y, z])
+    }
+
+    /// Creates a transform that scales along the axes by `(x, y, z)`, around the origin.
+    pub fn scaling(x: f64, y: f64, z: f64) -> Self {
+        HAffineTransform::from_parts(&HMatrix::from_diagonal(&[x, y, z]), [0.0; 3])
+    }
+
+    /// Creates a transform that rotates by `angle` around `axis` through the origin (see `h_rotation_from_axis_angle`).
+    /// If the axis does not have three components or is the zero vector, the function returns `None`.
+    pub fn rotation<T>(axis: &[T], angle: f64, measurement: Measurement) -> Option<Self>
+    where
+        T: Copy + Into<f64>,
+    {
+        Some(HAffineTransform::from_parts(&h_rotation_from_axis_angle(axis, angle, measurement)?, [0.0; 3]))
+    }
+
+    /// Creates a transform that applies the rotation represented by a quaternion.
+    pub fn from_quaternion(rotation: &HQuaternion) -> Self {
+        HAffineTransform::from_parts(&rotation.to_rotation_matrix(), [0.0; 3])
+    }
+
+    /// Creates a transform from a 4x4 homogeneous matrix. If the matrix is not 4x4 or its last row
+    /// is not `[0, 0, 0, 1]`, it is not affine and the function returns `None`.
+    pub fn from_matrix<T>(matrix: &HMatrix<T>) -> Option<Self>
+    where
+        T: Copy + Into<f64>,
+    {
+        if matrix.shape() != (4, 4) {
+            return None;
+        }
+        let matrix: HMatrix<f64> = matrix.map(|x| x.into());
+        if matrix.row(3)? != [0.0, 0.0, 0.0, 1.0] {
+            return None;
+        }
+        Some(HAffineTransform { matrix })
+    }
+
+    /// Returns the 4x4 homogeneous matrix.
+    pub fn matrix(&self) -> &HMatrix<f64> {
+        &self.matrix
+    }
+
+    /// Returns the transform that applies `self` first and then `next`.
+    pub fn then(&self, next: &HAffineTransform) -> HAffineTransform {
+        next * self
+    }
+
+    /// Calculates the inverse transform, which undoes this one. It is computed from the 3x3 linear part `A`
+    /// and translation `t` as `[A⁻¹ | -A⁻¹t]`, which is cheaper and more accurate than inverting the 4x4 matrix.
+    /// If the linear part is singular (for example a scaling by zero), the function returns `None`.
+    pub fn h_inverse(&self) -> Option<HAffineTransform> {
+        let linear_inverse: HMatrix<f64> = self.matrix.get_mult((0, 3), (0, 3))?.h_inverse()?;
+        let translation: Vec<f64> = self.matrix.get_col(3)?[..3].to_vec();
+        let moved_back: Vec<f64> = &linear_inverse * &translation;
+        Some(HAffineTransform::from_parts(&linear_inverse, [-moved_back[0], -moved_back[1], -moved_back[2]]))
+    }
+
+    /// Transforms a 3D point, which is affected by both the linear part and the translation.
+    /// If the point does not have three components, the function returns `None`.
+    pub fn h_transform_point<T>(&self, point: &[T]) -> Option<Vec<f64>>
+    where
+        T: Copy + Into<f64>,
+    {
+        self.transform_homogeneous(point, 1.0)
+    }
+
+    /// Transforms a 3D direction vector, which is only affected by the linear part and not by the translation.
+    /// If the vector does not have three components, the function returns `None`.
+    pub fn h_transform_vector<T>(&self, vector: &[T]) -> Option<Vec<f64>>
+    where
+        T: Copy + Into<f64>,
+    {
+        self.transform_homogeneous(vector, 0.0)
+    }
+
+    /// Multiplies `[x, y, z, w]` by the matrix and returns the first three components.
+    fn transform_homogeneous<T>(&self, vector: &[T], w: f64) -> Option<Vec<f64>>
+    where
+        T: Copy + Into<f64>,
+    {
+        if vector.len() != 3 {
+            return None;
+        }
+        let homogeneous: [f64; 4] = [vector[0].into(), vector[1].into(), vector[2].into(), w];
+        let mut result: Vec<f64> = homogeneous.h_linear_transform(&self.matrix)?;
+        result.truncate(3);
+        Some(result)
+    }
+}
+
+impl Mul<&HAffineTransform> for &HAffineTransform {
+    type Output = HAffineTransform;
+
+    /// Composes two transforms: `a * b` applies `b` first and then `a`.
+    fn mul(self, other: &HAffineTransform) -> HAffineTransform {
+        HAffineTransform {
+            matrix: &self.matrix * &other.matrix,
+        }
+    }
+}
+
+impl Mul for HAffineTransform {
+    type Output = HAffineTransform;
+
+    fn mul(self, other: HAffineTransform) -> HAffineTransform {
+        &self * &other
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::h_matrix;
+
+    fn assert_vec_close(actual: &[f64], expected: &[f64]) {
+        assert!(h_vector_sub(actual, expected).h_magnitude() < 1e-12, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn test_rotation_matrices() {
+        let rotation = h_rotation_from_axis_angle(&[0, 0, 2], 90.0, Measurement::Degrees).unwrap();
+        assert!(rotation.h_approx_eq(&h_matrix![0.0, -1.0, 0.0; 1.0, 0.0, 0.0; 0.0, 0.0, 1.0], 1e-12));
+        assert!(h_rotation_from_axis_angle(&[0, 0, 0], 1.0, Measurement::Radians).is_none());
+        assert!(h_rotation_from_axis_angle(&[0, 1], 1.0, Measurement::Radians).is_none());
+
+        let axis_angle = h_rotation_from_axis_angle(&[1.0, 2.0, 3.0], 0.7, Measurement::Radians).unwrap();
+        assert!((&axis_angle.h_transpose() * &axis_angle).h_approx_eq(&HMatrix::<f64>::identity(3), 1e-12));
+        assert!((axis_angle.h_determinant().unwrap() - 1.0).abs() < 1e-12);
+
+        let yaw = h_rotation_from_euler(0.0, 0.0, 90.0, Measurement::Degrees);
+        assert!(yaw.h_approx_eq(&rotation, 1e-12));
+        let euler = h_rotation_from_euler(0.3, -0.2, 1.1, Measurement::Radians);
+        let composed = &(&h_rotation_from_axis_angle(&[0, 0, 1], 1.1, Measurement::Radians).unwrap()
+            * &h_rotation_from_axis_angle(&[0, 1, 0], -0.2, Measurement::Radians).unwrap())
+            * &h_rotation_from_axis_angle(&[1, 0, 0], 0.3, Measurement::Radians).unwrap();
+        assert!(euler.h_approx_eq(&composed, 1e-12));
+    }
+
+    #[test]
+    fn test_quaternion() {
+        let quarter_turn = HQuaternion::from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap();
+        assert_vec_close(&quarter_turn.h_rotate_vector(&[1, 0, 0]).unwrap(), &[0.0, 1.0, 0.0]);
+        assert_vec_close(&(quarter_turn * quarter_turn).h_rotate_vector(&[1, 0, 0]).unwrap(), &[-1.0, 0.0, 0.0]);
+        assert!(quarter_turn.to_rotation_matrix().h_approx_eq(
+            &h_rotation_from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap(),
+            1e-12
+        ));
+
+        let q = HQuaternion::new(1.0, 2.0, 3.0, 4.0);
+        let product = q * q.h_inverse().unwrap();
+        assert!((product.w() - 1.0).abs() < 1e-12 && product.x().abs() < 1e-12);
+        assert_eq!(q.h_conjugate(), HQuaternion::new(1.0, -2.0, -3.0, -4.0));
+        assert_eq!(HQuaternion::new(0.0, 0.0, 0.0, 0.0).h_normalize(), Err(VectorError::ZeroVector));
+
+        // i * j = k
+        let i = HQuaternion::new(0.0, 1.0, 0.0, 0.0);
+        let j = HQuaternion::new(0.0, 0.0, 1.0, 0.0);
+        assert_eq!(i * j, HQuaternion::new(0.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_quaternion_slerp() {
+        let start = HQuaternion::identity();
+        let end = HQuaternion::from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap();
+        let halfway = start.h_slerp(&end, 0.5);
+        let expected = HQuaternion::from_axis_angle(&[0, 0, 1], 45.0, Measurement::Degrees).unwrap();
+        assert!((halfway.h_dot(&expected) - 1.0).abs() < 1e-12);
+        assert!((start.h_slerp(&end, 1.0).h_dot(&end) - 1.0).abs() < 1e-12);
+
+        // -end is the same rotation, so the interpolation still takes the short way.
+        let short_way = start.h_slerp(&-end, 0.5);
+        assert!((short_way.h_dot(&expected).abs() - 1.0).abs() < 1e-12);
+        assert_eq!(start.h_slerp(&start, 0.3), start);
+    }
+
+    #[test]
+    fn test_affine_transform() {
+        let scale = HAffineTransform::scaling(2.0, 2.0, 2.0);
+        let translate = HAffineTransform::translation(1.0, 0.0, -1.0);
+        let rotate = HAffineTransform::rotation(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap();
+        let transform = scale.then(&rotate).then(&translate);
+        assert_eq!(transform, &translate * &(&rotate * &scale));
+
+        assert_vec_close(&transform.h_transform_point(&[1, 0, 0]).unwrap(), &[1.0, 2.0, -1.0]);
+        assert_vec_close(&transform.h_transform_vector(&[1, 0, 0]).unwrap(), &[0.0, 2.0, 0.0]);
+        assert!(transform.h_transform_point(&[1, 0]).is_none());
+
+        let inverse = transform.h_inverse().unwrap();
+        assert!((&inverse * &transform).matrix().h_approx_eq(HAffineTransform::identity().matrix(), 1e-12));
+        assert!(HAffineTransform::scaling(1.0, 0.0, 1.0).h_inverse().is_none());
+
+        let from_quaternion = HAffineTransform::from_quaternion(
+            &HQuaternion::from_axis_angle(&[0, 0, 1], 90.0, Measurement::Degrees).unwrap(),
+        );
+        assert!(from_quaternion.matrix().h_approx_eq(rotate.matrix(), 1e-12));
+        assert_eq!(HAffineTransform::from_matrix(transform.matrix()), Some(transform));
+        assert!(HAffineTransform::from_matrix(&HMatrix::<f64>::identity(3)).is_none());
+    }
+}